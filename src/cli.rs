@@ -0,0 +1,220 @@
+use std::{env, path::PathBuf, time::Duration};
+
+use crate::{theme::Theme, Difficulty, Tab};
+
+pub const USAGE: &str = "\
+Usage: tui-idle-game [OPTIONS]
+
+Options:
+  --theme <NAME>          Color theme: classic, mono (default: classic)
+  --difficulty <LEVEL>    Balance for new games: easy, normal, hard (default: normal)
+  --simulate <SECONDS>    Run the game headless for SECONDS of game time and print a summary
+  --offline-cap-hours <HOURS>
+                          Most time away that earns offline gold; 0 disables it (default: 8)
+  --click-cooldown-ms <MS>
+                          Minimum time between clicks; 0 disables the cooldown (default: 500)
+  --max-timer-step <SECONDS>
+                          Longest step event timers take per update after a stall (default: 1)
+  --event-chance <N>      Random events started per minute on average, up to 60; 0 turns them off (default: 0.33)
+  --event-duration <SECONDS>
+                          How long random events last (default: 30)
+  --max-fps <N>           Most screen redraws per second, for slow connections (default: 30)
+  --tick-ms <MS>          How often gold accrues and timers advance, 10 to 1000 (default: 100)
+  --precision <0-4>       Decimal places in displayed numbers (default: 2)
+  --whole-gold-above <N>  Show header gold without decimals once you have N or more
+  --seed <N>              Seed for crits, events and nuggets in new games, for repeatable runs
+  --start-gold <N>        Start new games with N gold (for testing)
+  --save-path <PATH>      Where to keep the save file (default: $TUI_IDLE_SAVE, then the data dir)
+  --profile <NAME>        Play the named profile, saved separately as save-NAME.json
+  --stats-json <PATH>     Write final session stats to PATH as JSON when quitting
+  --confirm-quit          Ask for confirmation before quitting
+  --compact               Use a single-pane layout for small terminals
+  --banner                Show an ASCII-art title on terminals over 30 rows tall
+  --accessible            Mark prices with a check or cross, not just green or red
+  --start-tab <TAB>       Tab to open on: passive, click, achievements, stats, prestige
+  --wrap-selection        Up on the first row goes to the last, Down on the last to the first
+  --bell                  Ring the terminal bell on purchases and achievement unlocks
+  --debug                 Enable cheat keys (g adds 1M gold, G multiplies gold by 10) and
+                          show draw/tick timings and FPS
+  -h, --help              Print this help";
+
+#[derive(Default)]
+pub struct Options {
+    pub theme: Option<Theme>,
+    pub simulate: Option<f64>,
+    pub difficulty: Option<Difficulty>,
+    pub click_cooldown: Option<Duration>,
+    pub offline_cap: Option<Duration>,
+    pub start_gold: Option<f64>,
+    pub seed: Option<u64>,
+    pub max_timer_step: Option<f64>,
+    // Per second, converted from the per-minute rate on the command line
+    pub event_chance: Option<f64>,
+    pub event_duration: Option<f64>,
+    pub precision: Option<usize>,
+    pub whole_gold_above: Option<f64>,
+    pub max_fps: Option<u32>,
+    pub tick_interval: Option<Duration>,
+    pub debug: bool,
+    pub bell: bool,
+    pub confirm_quit: bool,
+    pub compact: bool,
+    pub banner: bool,
+    pub accessible: bool,
+    pub wrap_selection: bool,
+    pub start_tab: Option<Tab>,
+    pub save_path: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub stats_json: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Options {
+    pub fn parse() -> Result<Self, String> {
+        Self::parse_from(env::args().skip(1))
+    }
+
+    fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--theme" => {
+                    let name = next_value(&mut args, &arg)?;
+                    options.theme = Some(Theme::by_name(&name)
+                        .ok_or_else(|| format!("unknown theme '{}'", name))?);
+                }
+                "--start-tab" => {
+                    let name = next_value(&mut args, &arg)?;
+                    options.start_tab = Some(Tab::by_name(&name)
+                        .ok_or_else(|| format!("unknown tab '{}' (expected passive, click, achievements, stats or prestige)", name))?);
+                }
+                "--difficulty" => {
+                    let name = next_value(&mut args, &arg)?;
+                    options.difficulty = Some(Difficulty::by_name(&name)
+                        .ok_or_else(|| format!("unknown difficulty '{}'", name))?);
+                }
+                "--simulate" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let seconds: f64 = value.parse()
+                        .map_err(|_| format!("invalid number of seconds '{}'", value))?;
+                    if !seconds.is_finite() || seconds <= 0.0 {
+                        return Err("--simulate needs a positive number of seconds".to_string());
+                    }
+                    options.simulate = Some(seconds);
+                }
+                "--click-cooldown-ms" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let millis: u64 = value.parse()
+                        .map_err(|_| format!("invalid cooldown '{}' (expected whole milliseconds)", value))?;
+                    options.click_cooldown = Some(Duration::from_millis(millis));
+                }
+                "--offline-cap-hours" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let hours: f64 = value.parse()
+                        .map_err(|_| format!("invalid number of hours '{}'", value))?;
+                    let cap = Duration::try_from_secs_f64(hours * 3600.0)
+                        .map_err(|_| "--offline-cap-hours needs zero or a positive number of hours".to_string())?;
+                    options.offline_cap = Some(cap);
+                }
+                "--max-timer-step" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let seconds: f64 = value.parse()
+                        .map_err(|_| format!("invalid number of seconds '{}'", value))?;
+                    if !seconds.is_finite() || seconds <= 0.0 {
+                        return Err("--max-timer-step needs a positive number of seconds".to_string());
+                    }
+                    options.max_timer_step = Some(seconds);
+                }
+                "--event-chance" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let per_minute: f64 = value.parse().ok()
+                        .filter(|rate| (0.0..=60.0).contains(rate))
+                        .ok_or_else(|| format!("invalid event chance '{}' (expected 0 to 60 events per minute)", value))?;
+                    options.event_chance = Some(per_minute / 60.0);
+                }
+                "--event-duration" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let seconds: f64 = value.parse()
+                        .map_err(|_| format!("invalid number of seconds '{}'", value))?;
+                    if !seconds.is_finite() || seconds <= 0.0 {
+                        return Err("--event-duration needs a positive number of seconds".to_string());
+                    }
+                    options.event_duration = Some(seconds);
+                }
+                "--precision" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let precision: usize = value.parse().ok()
+                        .filter(|p| *p <= 4)
+                        .ok_or_else(|| format!("invalid precision '{}' (expected 0 to 4)", value))?;
+                    options.precision = Some(precision);
+                }
+                "--whole-gold-above" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let gold: f64 = value.parse()
+                        .map_err(|_| format!("invalid amount of gold '{}'", value))?;
+                    if !gold.is_finite() || gold < 0.0 {
+                        return Err("--whole-gold-above needs zero or a positive amount".to_string());
+                    }
+                    options.whole_gold_above = Some(gold);
+                }
+                "--max-fps" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let fps: u32 = value.parse().ok()
+                        .filter(|fps| *fps > 0)
+                        .ok_or_else(|| format!("invalid frame rate '{}' (expected a whole number above 0)", value))?;
+                    options.max_fps = Some(fps);
+                }
+                "--tick-ms" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let millis: u64 = value.parse().ok()
+                        .filter(|millis| (10..=1000).contains(millis))
+                        .ok_or_else(|| format!("invalid tick length '{}' (expected 10 to 1000 milliseconds)", value))?;
+                    options.tick_interval = Some(Duration::from_millis(millis));
+                }
+                "--seed" => {
+                    let value = next_value(&mut args, &arg)?;
+                    options.seed = Some(value.parse()
+                        .map_err(|_| format!("invalid seed '{}' (expected a whole number)", value))?);
+                }
+                "--start-gold" => {
+                    let value = next_value(&mut args, &arg)?;
+                    let gold: f64 = value.parse()
+                        .map_err(|_| format!("invalid amount of gold '{}'", value))?;
+                    if !gold.is_finite() || gold < 0.0 {
+                        return Err("--start-gold needs zero or a positive amount".to_string());
+                    }
+                    options.start_gold = Some(gold);
+                }
+                "--debug" => options.debug = true,
+                "--bell" => options.bell = true,
+                "--confirm-quit" => options.confirm_quit = true,
+                "--compact" => options.compact = true,
+                "--banner" => options.banner = true,
+                "--accessible" => options.accessible = true,
+                "--wrap-selection" => options.wrap_selection = true,
+                "--save-path" => options.save_path = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+                "--profile" => {
+                    let name = next_value(&mut args, &arg)?;
+                    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                        return Err(format!("invalid profile name '{}' (use letters, digits, '-' and '_')", name));
+                    }
+                    options.profile = Some(name);
+                }
+                "--stats-json" => options.stats_json = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("unexpected argument '{}'", arg)),
+            }
+        }
+
+        if options.save_path.is_some() && options.profile.is_some() {
+            return Err("--save-path and --profile can't be used together".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{} requires a value", flag))
+}
@@ -0,0 +1,89 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of the current time for game logic, so timing can be faked in tests.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    /// Wall-clock time in whole seconds since the Unix epoch, for save timestamps.
+    fn unix_time(&self) -> u64;
+}
+
+/// Calendar day number (days since 1970-01-01) of `unix_time` in the local timezone,
+/// so "yesterday" follows the player's midnight rather than UTC's.
+pub fn local_day(unix_time: u64) -> i64 {
+    let local = unix_time as i64 + utc_offset_seconds(unix_time);
+    local.div_euclid(24 * 60 * 60)
+}
+
+#[cfg(unix)]
+fn utc_offset_seconds(unix_time: u64) -> i64 {
+    let time = unix_time as libc::time_t;
+    // SAFETY: localtime_r only writes to the tm we hand it
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
+    }
+    tm.tm_gmtoff as i64
+}
+
+// No timezone lookup elsewhere; days roll over at UTC midnight
+#[cfg(not(unix))]
+fn utc_offset_seconds(_unix_time: u64) -> i64 {
+    0
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn unix_time(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+pub use mock::MockClock;
+
+#[cfg(test)]
+mod mock {
+    use std::{cell::Cell, rc::Rc, time::{Duration, Instant}};
+
+    use super::Clock;
+
+    // Arbitrary fixed starting point for the fake wall clock
+    const START_UNIX_TIME: Duration = Duration::from_secs(1_700_000_000);
+
+    /// Clock that only moves when told to. Clones share the same time, so a
+    /// test can keep one handle while the game owns another.
+    #[derive(Clone)]
+    pub struct MockClock {
+        now: Rc<Cell<Instant>>,
+        since_epoch: Rc<Cell<Duration>>,
+    }
+
+    impl MockClock {
+        pub fn new() -> Self {
+            Self {
+                now: Rc::new(Cell::new(Instant::now())),
+                since_epoch: Rc::new(Cell::new(START_UNIX_TIME)),
+            }
+        }
+
+        pub fn advance(&self, duration: Duration) {
+            self.now.set(self.now.get() + duration);
+            self.since_epoch.set(self.since_epoch.get() + duration);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+
+        fn unix_time(&self) -> u64 {
+            self.since_epoch.get().as_secs()
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use serde::Deserialize;
+use std::{fs, io, path::Path};
+
+use crate::{Achievement, AchievementType};
+
+// One entry of achievements.json
+#[derive(Deserialize)]
+struct AchievementDef {
+    name: String,
+    description: String,
+    #[serde(rename = "type")]
+    kind: String,
+    target: f64,
+    // Production multiplier once completed; no bonus if left out
+    #[serde(default = "no_reward")]
+    reward: f64,
+}
+
+fn no_reward() -> f64 {
+    1.0
+}
+
+pub fn default_achievements() -> Vec<Achievement> {
+    vec![
+        Achievement::new("First Steps", "Earn 100 total gold", AchievementType::TotalGold(100.0), 1.02),
+        Achievement::new("Getting Rich", "Earn 10,000 total gold", AchievementType::TotalGold(10000.0), 1.05),
+        Achievement::new("Millionaire", "Earn 1,000,000 total gold", AchievementType::TotalGold(1000000.0), 1.10),
+        Achievement::new("Passive Income", "Reach 10 gold per second", AchievementType::GoldPerSecond(10.0), 1.05),
+        Achievement::new("Gold Rush", "Reach 100 gold per second", AchievementType::GoldPerSecond(100.0), 1.10),
+        Achievement::new("Click Master", "Click 1,000 times", AchievementType::TotalClicks(1000), 1.05),
+        Achievement::new("Power Clicker", "Reach 50 gold per click", AchievementType::ClickPower(50.0), 1.05),
+        Achievement::new("Upgrade Collector", "Purchase 50 upgrades", AchievementType::UpgradesPurchased(50), 1.05),
+    ]
+}
+
+/// Reads achievement definitions from `path`, falling back to the built-in set when the
+/// file is missing, unreadable or has no usable entries. Invalid entries are skipped and
+/// reported as warnings rather than failing startup.
+pub fn load_achievements(path: &Path) -> (Vec<Achievement>, Vec<String>) {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return (default_achievements(), Vec::new()),
+        Err(err) => return (default_achievements(), vec![format!("couldn't read {}: {}", path.display(), err)]),
+    };
+    parse_achievements(&text)
+}
+
+fn parse_achievements(text: &str) -> (Vec<Achievement>, Vec<String>) {
+    let defs: Vec<AchievementDef> = match serde_json::from_str(text) {
+        Ok(defs) => defs,
+        Err(err) => return (default_achievements(), vec![format!("{}; using the built-in achievements", err)]),
+    };
+
+    let mut warnings = Vec::new();
+    let mut achievements: Vec<Achievement> = Vec::new();
+    for def in defs {
+        match validate(&def) {
+            // Saves match achievements by name, so a repeat would share the first one's progress
+            Ok(_) if achievements.iter().any(|a| a.name == def.name) => {
+                warnings.push(format!("'{}' is defined more than once; keeping the first", def.name));
+            }
+            Ok(achievement_type) => {
+                achievements.push(Achievement::new(&def.name, &def.description, achievement_type, def.reward));
+            }
+            Err(message) => warnings.push(format!("skipping '{}': {}", def.name, message)),
+        }
+    }
+
+    if achievements.is_empty() {
+        warnings.push("no valid achievements; using the built-in ones".to_string());
+        return (default_achievements(), warnings);
+    }
+    (achievements, warnings)
+}
+
+fn validate(def: &AchievementDef) -> Result<AchievementType, String> {
+    if !def.target.is_finite() || def.target <= 0.0 {
+        return Err(format!("target must be positive, got {}", def.target));
+    }
+    if !def.reward.is_finite() || def.reward < 1.0 {
+        return Err(format!("reward must be at least 1.0, got {}", def.reward));
+    }
+
+    let count = def.target.ceil() as u64;
+    match def.kind.as_str() {
+        "TotalGold" => Ok(AchievementType::TotalGold(def.target)),
+        "GoldPerSecond" => Ok(AchievementType::GoldPerSecond(def.target)),
+        "TotalClicks" => Ok(AchievementType::TotalClicks(count)),
+        "ClickPower" => Ok(AchievementType::ClickPower(def.target)),
+        "UpgradesPurchased" => Ok(AchievementType::UpgradesPurchased(count)),
+        other => Err(format!(
+            "unknown type '{}' (expected TotalGold, GoldPerSecond, TotalClicks, ClickPower or UpgradesPurchased)",
+            other
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_entries_are_skipped_with_warnings() {
+        let (achievements, warnings) = parse_achievements(r#"[
+            { "name": "Hoarder", "description": "Earn 1B gold", "type": "TotalGold", "target": 1e9, "reward": 1.2 },
+            { "name": "Broken", "description": "", "type": "TotalGold", "target": -5 },
+            { "name": "Mystery", "description": "", "type": "Prestiges", "target": 3 }
+        ]"#);
+        assert_eq!(achievements.len(), 1);
+        assert_eq!(achievements[0].name, "Hoarder");
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    }
+
+    #[test]
+    fn unusable_file_falls_back_to_defaults() {
+        let (achievements, warnings) = parse_achievements("not json");
+        assert_eq!(achievements.len(), default_achievements().len());
+        assert_eq!(warnings.len(), 1);
+    }
+}
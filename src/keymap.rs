@@ -0,0 +1,286 @@
+use crossterm::event::KeyCode;
+use std::{collections::HashMap, fs, io, path::Path};
+
+/// Everything a key press can do outside of overlays and the filter prompt.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Quit,
+    Click,
+    CollectNugget,
+    BuyUpgrade,
+    Sell,
+    ShowDetails,
+    BuyAll,
+    BuyToMilestone,
+    ToggleAutoBuy,
+    Undo,
+    FocusNext,
+    FocusPrevious,
+    SelectUp,
+    SelectDown,
+    PageUp,
+    PageDown,
+    SelectAffordable,
+    Recommend,
+    TabPassive,
+    TabClick,
+    TabAchievements,
+    ToggleCompleted,
+    TabStats,
+    TabPrestige,
+    Prestige,
+    PrestigeHistory,
+    SetGoal,
+    ToggleHelp,
+    TogglePrecise,
+    News,
+    Save,
+    CycleTheme,
+    CycleNumberFormat,
+    CycleSort,
+    Filter,
+    ClearFilter,
+    Pause,
+    Reset,
+    Export,
+    Import,
+    Share,
+}
+
+impl Action {
+    // Name used for the action in keybinds.toml, paired with its default key
+    const ALL: [(Action, &'static str, KeyCode); 41] = [
+        (Action::Quit, "quit", KeyCode::Char('q')),
+        (Action::Click, "click", KeyCode::Char(' ')),
+        (Action::CollectNugget, "collect_nugget", KeyCode::Char('n')),
+        (Action::BuyUpgrade, "buy_upgrade", KeyCode::Enter),
+        (Action::Sell, "sell", KeyCode::Char('S')),
+        (Action::ShowDetails, "show_details", KeyCode::Char('w')),
+        (Action::BuyAll, "buy_all", KeyCode::Char('b')),
+        (Action::BuyToMilestone, "buy_to_milestone", KeyCode::Char('m')),
+        (Action::ToggleAutoBuy, "toggle_auto_buy", KeyCode::Char('z')),
+        (Action::Undo, "undo", KeyCode::Char('u')),
+        (Action::FocusNext, "focus_next", KeyCode::Tab),
+        (Action::FocusPrevious, "focus_previous", KeyCode::BackTab),
+        (Action::SelectUp, "select_up", KeyCode::Up),
+        (Action::SelectDown, "select_down", KeyCode::Down),
+        (Action::PageUp, "page_up", KeyCode::PageUp),
+        (Action::PageDown, "page_down", KeyCode::PageDown),
+        (Action::SelectAffordable, "select_affordable", KeyCode::Char('a')),
+        (Action::Recommend, "recommend", KeyCode::Char('R')),
+        (Action::TabPassive, "tab_passive", KeyCode::Char('1')),
+        (Action::TabClick, "tab_click", KeyCode::Char('2')),
+        (Action::TabAchievements, "tab_achievements", KeyCode::Char('3')),
+        (Action::ToggleCompleted, "toggle_completed", KeyCode::Char('d')),
+        (Action::TabStats, "tab_stats", KeyCode::Char('4')),
+        (Action::TabPrestige, "tab_prestige", KeyCode::Char('5')),
+        (Action::Prestige, "prestige", KeyCode::Char('P')),
+        (Action::PrestigeHistory, "prestige_history", KeyCode::Char('l')),
+        (Action::SetGoal, "set_goal", KeyCode::Char('y')),
+        (Action::ToggleHelp, "toggle_help", KeyCode::Char('h')),
+        (Action::TogglePrecise, "toggle_precise", KeyCode::Char('v')),
+        (Action::News, "news", KeyCode::Char('c')),
+        (Action::Save, "save", KeyCode::Char('s')),
+        (Action::CycleTheme, "cycle_theme", KeyCode::Char('t')),
+        (Action::CycleNumberFormat, "cycle_number_format", KeyCode::Char('f')),
+        (Action::CycleSort, "cycle_sort", KeyCode::Char('o')),
+        (Action::Filter, "filter", KeyCode::Char('/')),
+        (Action::ClearFilter, "clear_filter", KeyCode::Esc),
+        (Action::Pause, "pause", KeyCode::Char('p')),
+        (Action::Reset, "reset", KeyCode::Char('r')),
+        (Action::Export, "export", KeyCode::Char('e')),
+        (Action::Import, "import", KeyCode::Char('i')),
+        (Action::Share, "share", KeyCode::Char('E')),
+    ];
+
+    fn from_name(name: &str) -> Option<Action> {
+        Self::ALL.iter().find(|(_, n, _)| *n == name).map(|&(action, _, _)| action)
+    }
+}
+
+pub struct Keymap {
+    bindings: HashMap<KeyCode, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self { bindings: Action::ALL.iter().map(|&(action, _, key)| (key, action)).collect() }
+    }
+}
+
+impl Keymap {
+    pub fn action_for(&self, key: KeyCode) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+
+    /// Reads overrides from `path` on top of the defaults. A missing file just means the
+    /// defaults; anything wrong inside it comes back as warnings instead of an error.
+    pub fn load(path: &Path) -> (Self, Vec<String>) {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (Self::default(), Vec::new()),
+            Err(err) => (Self::default(), vec![format!("couldn't read {}: {}", path.display(), err)]),
+        }
+    }
+
+    /// Parses the small subset of TOML we need: `action = "key"` or `action = ["key", ...]`
+    /// per line, with `#` comments. Listing an action replaces all of its default keys.
+    fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut overrides: Vec<(Action, Vec<KeyCode>)> = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut warn = |message: String| warnings.push(format!("line {}: {}", number + 1, message));
+
+            let Some((name, value)) = line.split_once('=') else {
+                warn(format!("expected `action = \"key\"`, found '{}'", line));
+                continue;
+            };
+            let name = name.trim();
+            let Some(action) = Action::from_name(name) else {
+                warn(format!("unknown action '{}'", name));
+                continue;
+            };
+            if overrides.iter().any(|(a, _)| *a == action) {
+                warn(format!("'{}' is bound more than once; keeping the first", name));
+                continue;
+            }
+            let Some(names) = parse_value(value.trim()) else {
+                warn(format!("value for '{}' must be a quoted key or a list of them", name));
+                continue;
+            };
+
+            let mut keys = Vec::new();
+            for key_name in names {
+                match parse_key(&key_name) {
+                    Some(key) => keys.push(key),
+                    None => warn(format!("unknown key '{}' for '{}'", key_name, name)),
+                }
+            }
+            overrides.push((action, keys));
+        }
+
+        // Explicit bindings win over defaults; among explicit ones the first listed wins
+        let mut bindings: HashMap<KeyCode, Action> = HashMap::new();
+        for (action, keys) in &overrides {
+            for key in keys {
+                match bindings.get(key) {
+                    Some(existing) => warnings.push(format!(
+                        "{} is bound to both {:?} and {:?}; keeping {:?}",
+                        key_label(*key), existing, action, existing
+                    )),
+                    None => {
+                        bindings.insert(*key, *action);
+                    }
+                }
+            }
+        }
+        for &(action, _, key) in &Action::ALL {
+            if overrides.iter().any(|(a, _)| *a == action) {
+                continue;
+            }
+            if let Some(existing) = bindings.get(&key) {
+                warnings.push(format!(
+                    "{} is now bound to {:?}, so {:?} has no key",
+                    key_label(key), existing, action
+                ));
+                continue;
+            }
+            bindings.insert(key, action);
+        }
+
+        (Self { bindings }, warnings)
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    // A '#' inside quotes is a key name, not a comment
+    let mut in_quotes = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(value: &str) -> Option<Vec<String>> {
+    match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(list) => list.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(parse_string)
+            .collect(),
+        None => parse_string(value).map(|key| vec![key]),
+    }
+}
+
+fn parse_string(value: &str) -> Option<String> {
+    value.strip_prefix('"')?.strip_suffix('"').map(str::to_string)
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let key = match name.to_ascii_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "insert" => KeyCode::Insert,
+        "delete" => KeyCode::Delete,
+        other => KeyCode::F(other.strip_prefix('f')?.parse().ok().filter(|n| (1..=12).contains(n))?),
+    };
+    Some(key)
+}
+
+fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => format!("'{}'", c),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_defaults() {
+        let (keymap, warnings) = Keymap::parse("select_up = [\"k\", \"Up\"]\nselect_down = \"j\" # vim\n");
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(keymap.action_for(KeyCode::Char('k')), Some(Action::SelectUp));
+        assert_eq!(keymap.action_for(KeyCode::Up), Some(Action::SelectUp));
+        assert_eq!(keymap.action_for(KeyCode::Char('j')), Some(Action::SelectDown));
+        assert_eq!(keymap.action_for(KeyCode::Down), None);
+        assert_eq!(keymap.action_for(KeyCode::Char('q')), Some(Action::Quit));
+    }
+
+    #[test]
+    fn bad_entries_warn_instead_of_failing() {
+        let (keymap, warnings) = Keymap::parse("jump = \"x\"\nclick = \"Banana\"\nquit = \"s\"\n");
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        // 's' now quits, so Save loses its only key
+        assert_eq!(keymap.action_for(KeyCode::Char('s')), Some(Action::Quit));
+        assert_eq!(keymap.action_for(KeyCode::Char('x')), None);
+    }
+}
@@ -1,21 +1,132 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton, MouseEvent, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
+    style::Modifier,
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, BorderType, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Sparkline, Tabs, Wrap},
     Frame, Terminal,
 };
+use serde::{Deserialize, Serialize};
 use std::{
-    io,
+    collections::VecDeque,
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+    process::ExitCode,
     time::{Duration, Instant},
 };
-use tokio::time::{interval, MissedTickBehavior};
+use tokio::{
+    sync::mpsc,
+    time::{interval, sleep_until, MissedTickBehavior},
+};
+
+mod cli;
+mod clock;
+mod content;
+mod keymap;
+mod paths;
+mod preferences;
+mod prestige;
+mod rng;
+mod save;
+mod simulate;
+mod theme;
+
+use clock::{Clock, SystemClock};
+use keymap::{Action, Keymap};
+use preferences::Preferences;
+use prestige::{PrestigeRecord, PrestigeUpgrade};
+use rng::Rng;
+use theme::Theme;
+
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+// Most frames drawn per second unless --max-fps says otherwise
+const DEFAULT_MAX_FPS: u32 = 30;
+// How often gold accrues and timers advance unless --tick-ms says otherwise
+const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(100);
+// Redraw at least this often even when nothing visible changed, so countdowns keep moving
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+const ACHIEVEMENT_BANNER_DURATION: Duration = Duration::from_secs(3);
+const CELEBRATION_DURATION: Duration = Duration::from_secs(2);
+const FLOATING_GAIN_DURATION: Duration = Duration::from_millis(500);
+const FLOATING_GAIN_CAPACITY: usize = 8;
+// On average one random event every three minutes (--event-chance)
+const DEFAULT_EVENT_CHANCE_PER_SECOND: f64 = 1.0 / 180.0;
+// Most recent prestiges kept in the history log
+const PRESTIGE_HISTORY_CAPACITY: usize = 100;
+// Seconds of gold/sec history kept for the Stats graph (one sample per second)
+const RATE_HISTORY_CAPACITY: usize = 120;
+// Gold, rates and production saturate here instead of reaching infinity. It's far below
+// f64::MAX so that sums and products of capped values still stay finite.
+const GOLD_CAP: f64 = 1e300;
+// Clicks landing within this long of the previous one extend the combo
+const COMBO_WINDOW: Duration = Duration::from_millis(1500);
+// Manual clicks this recent count toward the active income estimate
+const CLICK_RATE_WINDOW: Duration = Duration::from_secs(10);
+// Each combo step adds 10% click gold, up to double at 10 steps
+const COMBO_STEP_BONUS: f64 = 0.1;
+const MAX_COMBO: u32 = 10;
+// Every this many copies of an upgrade doubles its per-unit production
+const MILESTONE_INTERVAL: u64 = 25;
+// Daily streak bonus: 10 minutes of passive income (at least 100 gold) per streak day,
+// growing for the first week
+const DAILY_BONUS_SECONDS: f64 = 600.0;
+const DAILY_BONUS_MIN_GOLD: f64 = 100.0;
+const MAX_STREAK_BONUS_DAYS: u32 = 7;
+// First-run tutorial pages: a title and what to try
+const TUTORIAL_STEPS: [(&str, &str); 3] = [
+    ("Mine some gold", "Press Space or click the Mining panel to mine gold by hand."),
+    ("Buy an upgrade", "Pick a passive upgrade with Up/Down and press Enter to buy it. Passive upgrades earn gold every second, even while the game is closed."),
+    ("Explore the tabs", "Press 1-5 to switch between Passive, Click, Achievements, Stats and Prestige. H lists every key."),
+];
+// Changelog shown by the news overlay, and how wide scrollable documents are
+const NEWS: &str = include_str!("../CHANGELOG.md");
+const DOCUMENT_WIDTH: u16 = 70;
+// How long after buying the purchase can still be undone
+const UNDO_WINDOW: Duration = Duration::from_secs(10);
+// Share of a copy's price paid back when it's sold
+const SELL_REFUND: f64 = 0.5;
+// Golden nuggets: about one a minute, gone after 8s, worth a minute of income
+// (or at least 10 clicks' worth early on)
+const NUGGET_CHANCE_PER_SECOND: f64 = 1.0 / 60.0;
+const NUGGET_LIFETIME: f64 = 8.0;
+const NUGGET_REWARD_SECONDS: f64 = 60.0;
+const NUGGET_MIN_CLICKS: f64 = 10.0;
+// Default for --max-timer-step
+const DEFAULT_MAX_TIMER_STEP: f64 = 1.0;
+const ACHIEVEMENT_BAR_WIDTH: usize = 20;
+const GOAL_BAR_WIDTH: usize = 20;
+// Decimal places in displayed numbers unless --precision says otherwise
+const DEFAULT_PRECISION: usize = 2;
+// Past a quadrillion the grouped digits get too long to read, so they switch to scientific
+const GROUPED_DIGITS_LIMIT: f64 = 1e15;
+// PageUp/PageDown step before the list has been drawn
+const DEFAULT_PAGE_ROWS: usize = 5;
+// Time constant (seconds) for the Gold Progress gauge catching up with the real value
+const PROGRESS_EASING: f64 = 0.1;
+// Same for the header's gold counter, which trails passive income by about this long
+const GOLD_EASING: f64 = 0.1;
+// Below this many columns the mining panel stacks above the tab content
+const NARROW_LAYOUT_WIDTH: u16 = 80;
+// --banner art, drawn in place of the title on terminals at least BANNER_MIN_HEIGHT rows tall
+const BANNER: [&str; 5] = [
+    r"  ____  ___  _     ____    __  __ ___ _   _ _____ ",
+    r" / ___|/ _ \| |   |  _ \  |  \/  |_ _| \ | | ____|",
+    r"| |  _| | | | |   | | | | | |\/| || ||  \| |  _|  ",
+    r"| |_| | |_| | |___| |_| | | |  | || || |\  | |___ ",
+    r" \____|\___/|_____|____/  |_|  |_|___|_| \_|_____|",
+];
+const BANNER_MIN_HEIGHT: u16 = 31;
+// Smallest terminal the game draws in; anything smaller gets a notice instead
+const MIN_TERMINAL_WIDTH: u16 = 40;
+const MIN_TERMINAL_HEIGHT: u16 = 15;
+// Below this many rows the compact single-pane layout is used even without --compact
+const COMPACT_LAYOUT_HEIGHT: u16 = 22;
 
 #[derive(Clone)]
 struct Upgrade {
@@ -23,21 +134,56 @@ struct Upgrade {
     description: String,
     base_cost: f64,
     cost_multiplier: f64,
+    // Share of the price growth waived by prestige upgrades
+    cost_discount: f64,
     base_production: f64,
+    // Extra production per copy from SYNERGIES (0.12 = +12%)
+    synergy_bonus: f64,
     owned: u64,
+    // Lifetime stats, kept through prestige: gold this upgrade has produced (passive gold,
+    // or click gold for auto-clickers) and the most copies ever owned at once
+    lifetime_gold: f64,
+    max_owned: u64,
     upgrade_type: UpgradeType,
+    // Can't be bought until this is met (or a copy is already owned)
+    unlock_condition: Option<UnlockCondition>,
+    // Bought automatically whenever affordable, once Mine Managers is owned
+    auto_buy: bool,
+}
+
+#[derive(Clone)]
+enum UnlockCondition {
+    // At least this many copies of the named upgrade owned
+    Owned(&'static str, u64),
+    // This much gold earned in the current run
+    TotalGold(f64),
 }
 
 #[derive(Clone, PartialEq)]
 enum UpgradeType {
     Passive,
     Click,
+    // Production is automatic clicks per second rather than gold
+    AutoClick,
 }
 
+// Owning `source` upgrades boosts each `target` copy's production by `bonus` per source copy
+struct Synergy {
+    source: &'static str,
+    target: &'static str,
+    bonus: f64,
+}
+
+const SYNERGIES: [Synergy; 1] = [
+    Synergy { source: "Drill", target: "Pickaxe", bonus: 0.01 },
+];
+
 #[derive(Clone)]
 struct Achievement {
     name: String,
     description: String,
+    // Permanent production multiplier granted once completed (1.05 = +5%)
+    reward: f64,
     completed: bool,
     target: f64,
     achievement_type: AchievementType,
@@ -52,6 +198,113 @@ enum AchievementType {
     UpgradesPurchased(u64),
 }
 
+/// A big moment worth a full-width banner when play crosses it (see CELEBRATIONS).
+#[derive(Clone, Copy)]
+enum Milestone {
+    TotalGold(f64),
+    TotalClicks(u64),
+    // Copies owned of any single upgrade
+    Owned(u64),
+}
+
+impl Milestone {
+    fn threshold(self) -> f64 {
+        match self {
+            Milestone::TotalGold(gold) => gold,
+            Milestone::TotalClicks(clicks) => clicks as f64,
+            Milestone::Owned(copies) => copies as f64,
+        }
+    }
+}
+
+// Add rows here for more celebrations
+const CELEBRATIONS: [(Milestone, &str); 3] = [
+    (Milestone::TotalGold(1e6), "Your first million gold!"),
+    (Milestone::TotalClicks(1000), "One thousand clicks!"),
+    (Milestone::Owned(100), "A hundred of one upgrade!"),
+];
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum EventKind {
+    GoldRush,
+    LuckyStrike,
+    // Debuff: production drops while it lasts
+    CaveIn,
+}
+
+impl EventKind {
+    fn name(&self) -> &'static str {
+        match self {
+            EventKind::GoldRush => "Gold Rush",
+            EventKind::LuckyStrike => "Lucky Strike",
+            EventKind::CaveIn => "Cave-in",
+        }
+    }
+
+    fn duration(&self) -> f64 {
+        match self {
+            EventKind::GoldRush => 30.0,
+            EventKind::LuckyStrike => 30.0,
+            EventKind::CaveIn => 20.0,
+        }
+    }
+
+    fn production_multiplier(&self) -> f64 {
+        match self {
+            EventKind::GoldRush => 2.0,
+            EventKind::LuckyStrike => 1.0,
+            EventKind::CaveIn => 0.5,
+        }
+    }
+
+    fn is_debuff(&self) -> bool {
+        self.production_multiplier() < 1.0
+    }
+
+    // Number of guaranteed critical clicks granted by the event
+    fn crit_charges(&self) -> u32 {
+        match self {
+            EventKind::GoldRush => 0,
+            EventKind::LuckyStrike => 10,
+            EventKind::CaveIn => 0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ActiveEvent {
+    kind: EventKind,
+    remaining: f64,
+    crit_charges: u32,
+}
+
+impl ActiveEvent {
+    fn new(kind: EventKind, duration: f64) -> Self {
+        Self {
+            kind,
+            remaining: duration,
+            crit_charges: kind.crit_charges(),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.remaining <= 0.0 || (self.kind.crit_charges() > 0 && self.crit_charges == 0)
+    }
+
+    fn describe(&self) -> String {
+        match self.kind {
+            EventKind::GoldRush => format!("{}: {}x production ({:.0}s)", self.kind.name(), self.kind.production_multiplier(), self.remaining.ceil()),
+            EventKind::LuckyStrike => format!("{}: next {} clicks crit ({:.0}s)", self.kind.name(), self.crit_charges, self.remaining.ceil()),
+            EventKind::CaveIn => format!(
+                "{}: -{:.0}% production ({:.0}s)",
+                self.kind.name(),
+                (1.0 - self.kind.production_multiplier()) * 100.0,
+                self.remaining.ceil(),
+            ),
+        }
+    }
+}
+
 impl Upgrade {
     fn new(name: &str, description: &str, base_cost: f64, cost_multiplier: f64, base_production: f64, upgrade_type: UpgradeType) -> Self {
         Self {
@@ -59,18 +312,51 @@ impl Upgrade {
             description: description.to_string(),
             base_cost,
             cost_multiplier,
+            cost_discount: 0.0,
             base_production,
+            synergy_bonus: 0.0,
             owned: 0,
+            lifetime_gold: 0.0,
+            max_owned: 0,
             upgrade_type,
+            unlock_condition: None,
+            auto_buy: false,
         }
     }
 
+    fn unlocked_by(mut self, condition: UnlockCondition) -> Self {
+        self.unlock_condition = Some(condition);
+        self
+    }
+
+    // Price ratio between consecutive copies, after any prestige discount
+    fn cost_growth(&self) -> f64 {
+        1.0 + (self.cost_multiplier - 1.0) * (1.0 - self.cost_discount)
+    }
+
     fn current_cost(&self) -> f64 {
-        self.base_cost * self.cost_multiplier.powi(self.owned as i32)
+        self.base_cost * self.cost_growth().powi(self.owned as i32)
+    }
+
+    fn milestone_multiplier(&self) -> f64 {
+        2f64.powi((self.owned / MILESTONE_INTERVAL) as i32)
+    }
+
+    fn next_milestone(&self) -> u64 {
+        (self.owned / MILESTONE_INTERVAL + 1) * MILESTONE_INTERVAL
+    }
+
+    fn copies_to_milestone(&self) -> u64 {
+        self.next_milestone() - self.owned
+    }
+
+    // Production of a single copy at the current milestone level, synergies included
+    fn unit_production(&self) -> f64 {
+        (self.base_production * self.milestone_multiplier() * (1.0 + self.synergy_bonus)).min(GOLD_CAP)
     }
 
     fn current_production(&self) -> f64 {
-        self.base_production * self.owned as f64
+        (self.unit_production() * self.owned as f64).min(GOLD_CAP)
     }
 
     fn can_afford(&self, gold: f64) -> bool {
@@ -80,15 +366,80 @@ impl Upgrade {
     fn purchase(&mut self) -> f64 {
         let cost = self.current_cost();
         self.owned += 1;
+        self.max_owned = self.max_owned.max(self.owned);
         cost
     }
+
+    // Gives back the last copy for SELL_REFUND of what it cost; nothing if none are owned
+    fn sell(&mut self) -> f64 {
+        if self.owned == 0 {
+            return 0.0;
+        }
+        self.owned -= 1;
+        self.current_cost() * SELL_REFUND
+    }
+
+    // Total price of the next `count` copies; the costs form a geometric series
+    fn bulk_cost(&self, count: u64) -> f64 {
+        let ratio = self.cost_growth();
+        if ratio == 1.0 {
+            return self.current_cost() * count as f64;
+        }
+        self.current_cost() * (ratio.powf(count as f64) - 1.0) / (ratio - 1.0)
+    }
+
+    // What the owned copies would cost to buy again from scratch
+    fn rebuy_value(&self) -> f64 {
+        Upgrade { owned: 0, ..self.clone() }.bulk_cost(self.owned)
+    }
+
+    // Most copies that `gold` pays for in a row
+    fn max_affordable(&self, gold: f64) -> u64 {
+        let cost = self.current_cost();
+        if gold < cost {
+            return 0;
+        }
+        let ratio = self.cost_growth();
+        let estimate = if ratio == 1.0 {
+            gold / cost
+        } else {
+            (gold * (ratio - 1.0) / cost + 1.0).ln() / ratio.ln()
+        };
+        // The logarithm can land a hair either side of an integer, so settle it exactly
+        let mut count = estimate.floor().max(1.0) as u64;
+        while count > 1 && self.bulk_cost(count) > gold {
+            count -= 1;
+        }
+        while self.bulk_cost(count + 1) <= gold {
+            count += 1;
+        }
+        count
+    }
+
+    // How many upcoming copies cost strictly less than `limit`
+    fn copies_below(&self, limit: f64) -> u64 {
+        let cost = self.current_cost();
+        if limit <= cost {
+            return 0;
+        }
+        let ratio = self.cost_growth();
+        if ratio == 1.0 {
+            return u64::MAX;
+        }
+        let mut count = ((limit / cost).ln() / ratio.ln()).ceil() as u64;
+        while count > 0 && cost * ratio.powf((count - 1) as f64) >= limit {
+            count -= 1;
+        }
+        count
+    }
 }
 
 impl Achievement {
-    fn new(name: &str, description: &str, achievement_type: AchievementType) -> Self {
+    fn new(name: &str, description: &str, achievement_type: AchievementType, reward: f64) -> Self {
         Self {
             name: name.to_string(),
             description: description.to_string(),
+            reward,
             completed: false,
             target: match &achievement_type {
                 AchievementType::TotalGold(t) => *t,
@@ -102,39 +453,258 @@ impl Achievement {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
+enum SortMode {
+    Default,
+    Cost,
+    // Production gained per gold spent on the next copy
+    Roi,
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::Default => SortMode::Cost,
+            SortMode::Cost => SortMode::Roi,
+            SortMode::Roi => SortMode::Default,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortMode::Default => "default order",
+            SortMode::Cost => "cheapest first",
+            SortMode::Roi => "best value first",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum NumberFormat {
+    // 1.23K, 4.56M, 7.89B, ... then aa, ab, ...
+    Short,
+    // 1.23e15
+    Scientific,
+    // 1,234,567.89, switching to scientific from GROUPED_DIGITS_LIMIT up
+    Grouped,
+}
+
+impl NumberFormat {
+    fn next(self) -> Self {
+        match self {
+            NumberFormat::Short => NumberFormat::Scientific,
+            NumberFormat::Scientific => NumberFormat::Grouped,
+            NumberFormat::Grouped => NumberFormat::Short,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            NumberFormat::Short => "short",
+            NumberFormat::Scientific => "scientific",
+            NumberFormat::Grouped => "grouped digits",
+        }
+    }
+}
+
+/// Balance profile chosen when a game is created; saves remember it.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Difficulty {
+    // Double production, price growth halved (1.15x per copy becomes 1.075x)
+    Easy,
+    #[default]
+    Normal,
+    // Half production, price growth doubled (1.15x per copy becomes 1.30x)
+    Hard,
+}
+
+impl Difficulty {
+    fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    fn production_factor(self) -> f64 {
+        match self {
+            Difficulty::Easy => 2.0,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.5,
+        }
+    }
+
+    // Scales how far above 1.0 each upgrade's cost multiplier sits
+    fn cost_growth_factor(self) -> f64 {
+        1.0 / self.production_factor()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
 enum Tab {
     Passive,
     Click,
     Achievements,
+    Stats,
+    Prestige,
+}
+
+impl Tab {
+    fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "passive" => Some(Tab::Passive),
+            "click" => Some(Tab::Click),
+            "achievements" => Some(Tab::Achievements),
+            "stats" => Some(Tab::Stats),
+            "prestige" => Some(Tab::Prestige),
+            _ => None,
+        }
+    }
 }
 
 struct GameState {
     gold: f64,
     gold_per_second: f64,
+    // What gold_per_second would be without debuff events
+    base_gold_per_second: f64,
     click_power: f64,
+    // Combined bonus from completed achievement rewards
+    achievement_multiplier: f64,
+    auto_clicks_per_second: f64,
+    auto_click_progress: f64,
     total_gold_earned: f64,
+    total_gold_spent: f64,
     total_upgrades_purchased: u64,
     upgrades: Vec<Upgrade>,
     achievements: Vec<Achievement>,
     selected_upgrade: usize,
+    // Up on the first row goes to the last and Down on the last to the first (--wrap-selection)
+    wrap_selection: bool,
+    // Selection each tab had when it was last left, by tab_index
+    tab_selections: [usize; TAB_TITLES.len()],
     current_tab: Tab,
+    // Case-insensitive substring filter applied to the upgrade tabs
+    filter: String,
+    sort_mode: SortMode,
+    // Leave finished achievements out of the Achievements tab
+    hide_completed: bool,
+    number_format: NumberFormat,
+    // Decimal places shown by `format` (--precision)
+    precision: usize,
     last_update: Instant,
+    // Longest step event timers and rate sampling take in one update, in seconds
+    max_timer_step: f64,
+    // Odds per second of a random event starting, and how long events last in seconds
+    // when overridden (--event-chance, --event-duration)
+    event_chance: f64,
+    event_duration: Option<f64>,
     total_clicks: u64,
+    // Seconds the game has run unpaused, across every session and prestige
+    play_time: f64,
     show_help: bool,
+    paused: bool,
     last_click: Instant,
     click_cooldown: Duration,
+    combo: u32,
+    last_combo_time: Instant,
+    crit_chance: f64,
+    crit_multiplier: f64,
+    active_events: Vec<ActiveEvent>,
+    nugget: Option<Nugget>,
+    // Every random roll (crits, events, nuggets) comes from `rng`, started from `seed`
+    seed: u64,
+    rng: Rng,
+    rate_history: VecDeque<f64>,
+    // When each manual click in the last CLICK_RATE_WINDOW landed, oldest first
+    recent_clicks: VecDeque<Instant>,
+    // Time accumulated towards the next rate_history sample
+    history_timer: f64,
+    // Unix time (seconds) the loaded save was written, for offline earnings
+    saved_at: Option<u64>,
+    // Unspent prestige points; each one boosts production
+    prestige_points: u64,
+    prestige_count: u64,
+    // Oldest first, at most PRESTIGE_HISTORY_CAPACITY of the latest
+    prestige_history: Vec<PrestigeRecord>,
+    prestige_upgrades: Vec<PrestigeUpgrade>,
+    difficulty: Difficulty,
+    // Consecutive local calendar days played, and the last of them (days since 1970-01-01)
+    login_streak: u32,
+    last_play_day: Option<i64>,
+    // Set once the first-run tutorial is finished or skipped
+    tutorial_seen: bool,
+    // Gold amount the player is working toward, cleared once it's reached
+    goal: Option<f64>,
+    // What the latest buy action bought, until it's undone, expires or something else moves gold
+    last_purchase: Option<LastPurchase>,
+    clock: Box<dyn Clock>,
+}
+
+struct LastPurchase {
+    // (index into `upgrades`, copies bought, gold paid, max_owned before buying)
+    items: Vec<(usize, u64, f64, u64)>,
+    at: Instant,
+}
+
+// Clickable bonus that pops up somewhere in the mining panel for a few seconds
+struct Nugget {
+    // Position as fractions of the mining panel's inner width and height
+    x: f64,
+    y: f64,
+    // Seconds since it appeared
+    age: f64,
+}
+
+struct ClickOutcome {
+    gold: f64,
+    crit: bool,
+}
+
+// Reward for coming back on consecutive days
+struct DailyBonus {
+    streak: u32,
+    gold: f64,
+}
+
+// Gold credited for time spent away from the game
+struct OfflineReport {
+    away: Duration,
+    // `away` after the offline cap
+    credited: Duration,
+    gold: f64,
 }
 
 impl Default for GameState {
     fn default() -> Self {
+        Self::with_clock(Box::new(SystemClock))
+    }
+}
+
+impl GameState {
+    fn with_clock(clock: Box<dyn Clock>) -> Self {
+        let now = clock.now();
+        let seed = Rng::entropy_seed();
         let upgrades = vec![
             // Passive upgrades
             Upgrade::new("Pickaxe", "Basic mining tool (+0.1 gold/sec)", 10.0, 1.15, 0.1, UpgradeType::Passive),
             Upgrade::new("Shovel", "Dig faster (+0.5 gold/sec)", 50.0, 1.15, 0.5, UpgradeType::Passive),
-            Upgrade::new("Drill", "Mechanical mining (+2.0 gold/sec)", 250.0, 1.15, 2.0, UpgradeType::Passive),
+            Upgrade::new("Drill", "Mechanical mining (+2.0 gold/sec)", 250.0, 1.15, 2.0, UpgradeType::Passive)
+                .unlocked_by(UnlockCondition::Owned("Shovel", 5)),
             Upgrade::new("Excavator", "Heavy machinery (+8.0 gold/sec)", 1000.0, 1.15, 8.0, UpgradeType::Passive),
-            Upgrade::new("Mine Shaft", "Deep mining operation (+30.0 gold/sec)", 5000.0, 1.15, 30.0, UpgradeType::Passive),
+            Upgrade::new("Mine Shaft", "Deep mining operation (+30.0 gold/sec)", 5000.0, 1.15, 30.0, UpgradeType::Passive)
+                .unlocked_by(UnlockCondition::TotalGold(10_000.0)),
             Upgrade::new("Gold Factory", "Automated gold production (+100.0 gold/sec)", 25000.0, 1.15, 100.0, UpgradeType::Passive),
             
             // Click upgrades
@@ -143,513 +713,4289 @@ impl Default for GameState {
             Upgrade::new("Power Gloves", "Enhanced grip (+5 gold per click)", 500.0, 1.2, 5.0, UpgradeType::Click),
             Upgrade::new("Hydraulic Hammer", "Mechanized clicking (+10 gold per click)", 2500.0, 1.2, 10.0, UpgradeType::Click),
             Upgrade::new("Diamond Drill Bit", "Ultimate mining power (+25 gold per click)", 10000.0, 1.2, 25.0, UpgradeType::Click),
+            
+            // Auto-click upgrades (shown on the click tab)
+            Upgrade::new("Mining Robot", "Clicks for you (+0.2 clicks/sec)", 750.0, 1.25, 0.2, UpgradeType::AutoClick),
+            Upgrade::new("Robot Crew", "A whole team of clickers (+1 click/sec)", 7500.0, 1.25, 1.0, UpgradeType::AutoClick)
+                .unlocked_by(UnlockCondition::Owned("Mining Robot", 5)),
         ];
 
-        let achievements = vec![
-            Achievement::new("First Steps", "Earn 100 total gold", AchievementType::TotalGold(100.0)),
-            Achievement::new("Getting Rich", "Earn 10,000 total gold", AchievementType::TotalGold(10000.0)),
-            Achievement::new("Millionaire", "Earn 1,000,000 total gold", AchievementType::TotalGold(1000000.0)),
-            Achievement::new("Passive Income", "Reach 10 gold per second", AchievementType::GoldPerSecond(10.0)),
-            Achievement::new("Gold Rush", "Reach 100 gold per second", AchievementType::GoldPerSecond(100.0)),
-            Achievement::new("Click Master", "Click 1,000 times", AchievementType::TotalClicks(1000)),
-            Achievement::new("Power Clicker", "Reach 50 gold per click", AchievementType::ClickPower(50.0)),
-            Achievement::new("Upgrade Collector", "Purchase 50 upgrades", AchievementType::UpgradesPurchased(50)),
-        ];
+        let achievements = content::default_achievements();
 
         Self {
             gold: 0.0,
             gold_per_second: 0.0,
+            base_gold_per_second: 0.0,
             click_power: 1.0,
+            achievement_multiplier: 1.0,
+            auto_clicks_per_second: 0.0,
+            auto_click_progress: 0.0,
             total_gold_earned: 0.0,
+            total_gold_spent: 0.0,
             total_upgrades_purchased: 0,
             upgrades,
             achievements,
             selected_upgrade: 0,
+            wrap_selection: false,
+            tab_selections: [0; TAB_TITLES.len()],
             current_tab: Tab::Passive,
-            last_update: Instant::now(),
+            filter: String::new(),
+            sort_mode: SortMode::Default,
+            hide_completed: false,
+            number_format: NumberFormat::Short,
+            precision: DEFAULT_PRECISION,
+            last_update: now,
+            max_timer_step: DEFAULT_MAX_TIMER_STEP,
+            event_chance: DEFAULT_EVENT_CHANCE_PER_SECOND,
+            event_duration: None,
             total_clicks: 0,
+            play_time: 0.0,
             show_help: false,
-            last_click: Instant::now() - Duration::from_secs(1),
+            paused: false,
+            last_click: now - Duration::from_secs(1),
             click_cooldown: Duration::from_millis(500),
+            combo: 0,
+            last_combo_time: now,
+            crit_chance: 0.05,
+            crit_multiplier: 5.0,
+            active_events: Vec::new(),
+            nugget: None,
+            login_streak: 0,
+            last_play_day: None,
+            tutorial_seen: false,
+            goal: None,
+            last_purchase: None,
+            seed,
+            rng: Rng::new(seed),
+            rate_history: VecDeque::with_capacity(RATE_HISTORY_CAPACITY),
+            recent_clicks: VecDeque::new(),
+            history_timer: 0.0,
+            saved_at: None,
+            prestige_points: 0,
+            prestige_count: 0,
+            prestige_history: Vec::new(),
+            prestige_upgrades: prestige::shop(),
+            difficulty: Difficulty::Normal,
+            clock,
         }
     }
-}
 
-impl GameState {
-    fn update(&mut self) {
-        let now = Instant::now();
+    // Rescales the upgrade list from the current difficulty to `difficulty`. The factors are
+    // powers of two, so switching back and forth is exact.
+    fn set_difficulty(&mut self, difficulty: Difficulty) {
+        let production = difficulty.production_factor() / self.difficulty.production_factor();
+        let growth = difficulty.cost_growth_factor() / self.difficulty.cost_growth_factor();
+        for upgrade in &mut self.upgrades {
+            upgrade.base_production *= production;
+            upgrade.cost_multiplier = 1.0 + (upgrade.cost_multiplier - 1.0) * growth;
+        }
+        self.difficulty = difficulty;
+    }
+
+    // Advances the game and returns the indices of achievements completed this tick
+    fn update(&mut self) -> Vec<usize> {
+        let now = self.clock.now();
         let delta = now.duration_since(self.last_update).as_secs_f64();
         self.last_update = now;
 
-        // Calculate total gold per second from passive upgrades
-        self.gold_per_second = self.upgrades.iter()
-            .filter(|u| u.upgrade_type == UpgradeType::Passive)
-            .map(|u| u.current_production())
-            .sum();
-        
-        // Calculate click power from click upgrades
-        self.click_power = 1.0 + self.upgrades.iter()
-            .filter(|u| u.upgrade_type == UpgradeType::Click)
-            .map(|u| u.current_production())
-            .sum::<f64>();
+        if self.combo > 0 && now.duration_since(self.last_combo_time) > COMBO_WINDOW {
+            self.combo = 0;
+        }
 
-        // Add gold based on time passed
-        let gold_earned = self.gold_per_second * delta;
-        self.gold += gold_earned;
-        self.total_gold_earned += gold_earned;
+        self.advance(delta)
+    }
 
-        // Check achievements
-        let total_gold_earned = self.total_gold_earned;
-        let gold_per_second = self.gold_per_second;
-        let total_clicks = self.total_clicks;
-        let click_power = self.click_power;
-        let total_upgrades_purchased = self.total_upgrades_purchased;
-        
-        for achievement in &mut self.achievements {
-            let current_value = match achievement.achievement_type {
-                AchievementType::TotalGold(_) => total_gold_earned,
-                AchievementType::GoldPerSecond(_) => gold_per_second,
-                AchievementType::TotalClicks(_) => total_clicks as f64,
-                AchievementType::ClickPower(_) => click_power,
-                AchievementType::UpgradesPurchased(_) => total_upgrades_purchased as f64,
-            };
+    // Credits passive gold for the time since the loaded save was written, up to `cap`.
+    // Events only run while playing, so their multipliers don't apply.
+    fn collect_offline_earnings(&mut self, cap: Duration) -> Option<OfflineReport> {
+        let saved_at = self.saved_at.take()?;
+        // Quitting while paused keeps the pause going for the time the game was closed
+        if self.paused {
+            return None;
+        }
+        let away = Duration::from_secs(self.clock.unix_time().saturating_sub(saved_at));
+        // A zero cap means offline earnings are off, prestige bonuses included
+        let credited = if cap.is_zero() {
+            Duration::ZERO
+        } else {
+            away.min(cap + prestige::offline_cap_bonus(&self.prestige_upgrades))
+        };
 
-            if !achievement.completed && current_value >= achievement.target {
-                achievement.completed = true;
-            }
+        self.recompute_rates();
+        let gold = self.gold_per_second / self.event_multiplier() * credited.as_secs_f64();
+        if gold <= 0.0 {
+            return None;
         }
+        self.earn(gold);
+        self.credit_upgrades(UpgradeType::Passive, gold);
+        Some(OfflineReport { away, credited, gold })
     }
 
-    fn click_for_gold(&mut self) {
-        let now = Instant::now();
-        if now.duration_since(self.last_click) >= self.click_cooldown {
-            self.gold += self.click_power;
-            self.total_gold_earned += self.click_power;
-            self.total_clicks += 1;
-            self.last_click = now;
+    /// Records playing on `today` (a local day number). Coming back the day after the last
+    /// visit extends the streak and pays a bonus; a gap resets it; the same day changes nothing.
+    fn check_in(&mut self, today: i64) -> Option<DailyBonus> {
+        let last = self.last_play_day.replace(today);
+        if last == Some(today) {
+            return None;
+        }
+        if last != Some(today - 1) {
+            self.login_streak = 1;
+            return None;
         }
+
+        self.login_streak += 1;
+        let days = self.login_streak.min(MAX_STREAK_BONUS_DAYS) as f64;
+        self.recompute_rates();
+        let per_day = (self.gold_per_second / self.event_multiplier() * DAILY_BONUS_SECONDS).max(DAILY_BONUS_MIN_GOLD);
+        let gold = per_day * days;
+        self.earn(gold);
+        Some(DailyBonus { streak: self.login_streak, gold })
     }
 
-    fn get_current_upgrades(&self) -> Vec<&Upgrade> {
-        match self.current_tab {
-            Tab::Passive => self.upgrades.iter().filter(|u| u.upgrade_type == UpgradeType::Passive).collect(),
-            Tab::Click => self.upgrades.iter().filter(|u| u.upgrade_type == UpgradeType::Click).collect(),
-            Tab::Achievements => Vec::new(),
-        }
+    /// Prestige points a reset right now would award.
+    fn prestige_points_available(&self) -> u64 {
+        prestige::points_for(self.total_gold_earned)
     }
 
-    fn buy_selected(&mut self) {
-        if self.current_tab == Tab::Achievements {
-            return;
+    /// Trades this run's progress for prestige points. Gold, upgrades and run totals reset;
+    /// achievements, lifetime counters and everything bought in the prestige shop stay.
+    /// Returns the points gained, or 0 (changing nothing) if the run hasn't earned any.
+    fn prestige(&mut self) -> u64 {
+        let points = self.prestige_points_available();
+        if points == 0 {
+            return 0;
         }
 
-        let current_upgrades = self.get_current_upgrades();
-        if let Some(&upgrade) = current_upgrades.get(self.selected_upgrade) {
-            if upgrade.can_afford(self.gold) {
-                let upgrade_index = self.upgrades.iter().position(|u| 
-                    u.name == upgrade.name && u.upgrade_type == upgrade.upgrade_type
-                ).unwrap();
-                
-                let cost = self.upgrades[upgrade_index].purchase();
-                self.gold -= cost;
-                self.total_upgrades_purchased += 1;
-            }
+        self.prestige_points = self.prestige_points.saturating_add(points);
+        self.prestige_count += 1;
+        if self.prestige_history.len() == PRESTIGE_HISTORY_CAPACITY {
+            self.prestige_history.remove(0);
         }
+        self.prestige_history.push(PrestigeRecord {
+            at: self.clock.unix_time(),
+            gold_earned: self.total_gold_earned,
+            points,
+        });
+        for upgrade in &mut self.upgrades {
+            upgrade.owned = 0;
+        }
+        self.gold = prestige::starting_gold(&self.prestige_upgrades);
+        self.total_gold_earned = 0.0;
+        self.total_gold_spent = 0.0;
+        self.auto_click_progress = 0.0;
+        self.combo = 0;
+        self.active_events.clear();
+        self.rate_history.clear();
+        self.history_timer = 0.0;
+        self.selected_upgrade = 0;
+        self.last_purchase = None;
+        self.recompute_rates();
+        points
     }
 
-    fn select_next(&mut self) {
-        let max_index = match self.current_tab {
-            Tab::Passive => self.upgrades.iter().filter(|u| u.upgrade_type == UpgradeType::Passive).count(),
-            Tab::Click => self.upgrades.iter().filter(|u| u.upgrade_type == UpgradeType::Click).count(),
-            Tab::Achievements => self.achievements.len(),
+    // Buys a level of the prestige shop item at `index`, returning whether it was affordable
+    fn buy_prestige_upgrade(&mut self, index: usize) -> bool {
+        let Some(upgrade) = self.prestige_upgrades.get_mut(index) else {
+            return false;
         };
-        
-        if self.selected_upgrade < max_index.saturating_sub(1) {
-            self.selected_upgrade += 1;
+        if !upgrade.can_afford(self.prestige_points) {
+            return false;
         }
+        self.prestige_points -= upgrade.current_cost();
+        upgrade.owned += 1;
+        self.last_purchase = None;
+        self.recompute_rates();
+        true
     }
 
-    fn select_previous(&mut self) {
-        if self.selected_upgrade > 0 {
-            self.selected_upgrade -= 1;
+    // Restarts the tick clock so time spent before now isn't paid out by the next update
+    fn resume_clock(&mut self) {
+        self.last_update = self.clock.now();
+    }
+
+    // Adds to both the spendable and the lifetime gold, saturating at the cap
+    fn earn(&mut self, gold: f64) {
+        self.gold = (self.gold + gold).min(GOLD_CAP);
+        self.total_gold_earned = (self.total_gold_earned + gold).min(GOLD_CAP);
+    }
+
+    // Splits `gold` between the owned upgrades of `kind` by their share of its production
+    fn credit_upgrades(&mut self, kind: UpgradeType, gold: f64) {
+        let gold = gold.min(GOLD_CAP);
+        let total: f64 = self.upgrades.iter()
+            .filter(|u| u.upgrade_type == kind)
+            .map(|u| u.current_production())
+            .sum();
+        if total <= 0.0 {
+            return;
+        }
+        for upgrade in self.upgrades.iter_mut().filter(|u| u.upgrade_type == kind) {
+            upgrade.lifetime_gold = (upgrade.lifetime_gold + gold * upgrade.current_production() / total).min(GOLD_CAP);
         }
     }
 
-    fn switch_tab(&mut self, tab: Tab) {
-        if self.current_tab != tab {
-            self.current_tab = tab;
-            self.selected_upgrade = 0;
+    // Steps the game forward by `delta` seconds without reading the clock, so
+    // headless simulations can drive it with synthetic time
+    fn advance(&mut self, delta: f64) -> Vec<usize> {
+        // Time keeps moving while paused so unpausing doesn't pay out the gap
+        if self.paused {
+            self.recompute_rates();
+            return Vec::new();
         }
+
+        // A huge delta means the process was stalled (e.g. laptop sleep), so timers only
+        // move by a bounded step; the rest still pays passive gold, minus event boosts
+        let timer_delta = delta.min(self.max_timer_step);
+        let stalled = delta - timer_delta;
+        self.play_time += timer_delta;
+        self.tick_events(timer_delta);
+        self.tick_nugget(timer_delta);
+        self.recompute_rates();
+
+        // Add gold based on time passed
+        let gold_earned = self.gold_per_second * timer_delta
+            + self.gold_per_second / self.event_multiplier() * stalled;
+        self.earn(gold_earned);
+        self.credit_upgrades(UpgradeType::Passive, gold_earned);
+
+        // Auto-clickers build up fractional clicks and fire the whole ones
+        self.auto_click_progress += self.auto_clicks_per_second * delta;
+        let auto_clicks = self.auto_click_progress.floor();
+        if auto_clicks > 0.0 {
+            self.auto_click_progress -= auto_clicks;
+            let click_gold = self.click_power * auto_clicks;
+            self.earn(click_gold);
+            self.total_clicks += auto_clicks as u64;
+            self.credit_upgrades(UpgradeType::AutoClick, click_gold);
+        }
+        self.run_auto_buyers();
+
+        self.history_timer += timer_delta;
+        while self.history_timer >= 1.0 {
+            self.history_timer -= 1.0;
+            if self.rate_history.len() == RATE_HISTORY_CAPACITY {
+                self.rate_history.pop_front();
+            }
+            self.rate_history.push_back(self.gold_per_second);
+        }
+
+        // Check achievements
+        let values: Vec<f64> = self.achievements.iter()
+            .map(|a| self.achievement_value(&a.achievement_type))
+            .collect();
+
+        let mut newly_completed = Vec::new();
+        for (index, (achievement, current_value)) in self.achievements.iter_mut().zip(values).enumerate() {
+            if !achievement.completed && current_value >= achievement.target {
+                achievement.completed = true;
+                newly_completed.push(index);
+            }
+        }
+
+        newly_completed
     }
 
-    fn format_number(num: f64) -> String {
-        if num >= 1_000_000.0 {
-            format!("{:.2}M", num / 1_000_000.0)
-        } else if num >= 1_000.0 {
-            format!("{:.2}K", num / 1_000.0)
-        } else {
-            format!("{:.2}", num)
+    /// Spends gold on upgrades marked for auto-buy, cheapest first, until none of them is
+    /// affordable. Does nothing until the Mine Managers prestige upgrade is owned.
+    fn run_auto_buyers(&mut self) {
+        if !prestige::auto_buy_unlocked(&self.prestige_upgrades) {
+            return;
+        }
+        let mut bought = false;
+        while let Some(index) = self.upgrades.iter()
+            .enumerate()
+            .filter(|(_, u)| u.auto_buy && self.can_buy(u))
+            .min_by(|(_, a), (_, b)| a.current_cost().total_cmp(&b.current_cost()))
+            .map(|(index, _)| index)
+        {
+            self.purchase(index);
+            bought = true;
+        }
+        if bought {
+            // Undo only knows about the player's own buys
+            self.last_purchase = None;
+            self.recompute_rates();
         }
     }
-}
 
-struct App {
-    game_state: GameState,
-    should_quit: bool,
-}
+    /// Flips auto-buy on the selected upgrade, returning its name and new setting, or None
+    /// when nothing buyable is selected.
+    fn toggle_auto_buy(&mut self) -> Option<(String, bool)> {
+        let selected = self.get_current_upgrades().get(self.selected_upgrade)
+            .map(|u| (u.name.clone(), u.upgrade_type.clone()))?;
+        let upgrade = self.upgrades.iter_mut().find(|u| u.name == selected.0 && u.upgrade_type == selected.1)?;
+        upgrade.auto_buy = !upgrade.auto_buy;
+        Some((selected.0, upgrade.auto_buy))
+    }
 
-impl App {
-    fn new() -> Self {
-        Self {
-            game_state: GameState::default(),
-            should_quit: false,
-        }
+    // Where play stands against each of CELEBRATIONS, in the same order
+    fn milestone_values(&self) -> [f64; CELEBRATIONS.len()] {
+        CELEBRATIONS.map(|(milestone, _)| match milestone {
+            Milestone::TotalGold(_) => self.total_gold_earned,
+            Milestone::TotalClicks(_) => self.total_clicks as f64,
+            Milestone::Owned(_) => self.upgrades.iter().map(|u| u.owned).max().unwrap_or(0) as f64,
+        })
     }
 
-    fn on_tick(&mut self) {
-        self.game_state.update();
+    // Clears the goal once there's that much gold on hand and returns it
+    fn check_goal(&mut self) -> Option<f64> {
+        let goal = self.goal.filter(|&goal| self.gold >= goal)?;
+        self.goal = None;
+        Some(goal)
     }
 
-    fn on_key(&mut self, key: KeyCode) {
-        match key {
-            KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Char(' ') => self.game_state.click_for_gold(),
-            KeyCode::Enter => self.game_state.buy_selected(),
-            KeyCode::Up => self.game_state.select_previous(),
-            KeyCode::Down => self.game_state.select_next(),
-            KeyCode::Char('h') => self.game_state.show_help = !self.game_state.show_help,
-            KeyCode::Char('1') => self.game_state.switch_tab(Tab::Passive),
-            KeyCode::Char('2') => self.game_state.switch_tab(Tab::Click),
-            KeyCode::Char('3') => self.game_state.switch_tab(Tab::Achievements),
-            _ => {}
+    // Time until the goal at the current rate; None when it isn't getting any closer
+    fn goal_eta(&self) -> Option<Duration> {
+        let remaining = self.goal? - self.gold;
+        if remaining <= 0.0 {
+            return Some(Duration::ZERO);
         }
+        (self.gold_per_second > 0.0).then(|| Duration::try_from_secs_f64(remaining / self.gold_per_second).ok()).flatten()
     }
-}
 
-fn ui(f: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(10),
-            Constraint::Length(3),
-        ].as_ref())
-        .split(f.area());
+    // The stat an achievement of this type measures
+    fn achievement_value(&self, achievement_type: &AchievementType) -> f64 {
+        match achievement_type {
+            AchievementType::TotalGold(_) => self.total_gold_earned,
+            AchievementType::GoldPerSecond(_) => self.gold_per_second,
+            AchievementType::TotalClicks(_) => self.total_clicks as f64,
+            AchievementType::ClickPower(_) => self.click_power,
+            AchievementType::UpgradesPurchased(_) => self.total_upgrades_purchased as f64,
+        }
+    }
 
-    // Header
-    let header = Paragraph::new(vec![
-        Line::from(vec![
-            Span::styled("TERMINAL GOLD MINE", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-        ]),
-        Line::from(vec![
-            Span::raw("Gold: "),
-            Span::styled(GameState::format_number(app.game_state.gold), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::raw(" | Rate: "),
-            Span::styled(format!("{}/sec", GameState::format_number(app.game_state.gold_per_second)), Style::default().fg(Color::Green)),
-            Span::raw(" | Click: +"),
-            Span::styled(GameState::format_number(app.game_state.click_power), Style::default().fg(Color::Cyan)),
-            Span::raw(" | Total: "),
-            Span::styled(GameState::format_number(app.game_state.total_gold_earned), Style::default().fg(Color::Magenta)),
-        ])
-    ])
-    .block(Block::default().borders(Borders::ALL).title("Status"))
-    .alignment(Alignment::Center);
-    f.render_widget(header, chunks[0]);
+    fn event_multiplier(&self) -> f64 {
+        self.active_events.iter()
+            .map(|e| e.kind.production_multiplier().max(0.0))
+            .product()
+    }
 
-    // Main content - now full width instead of split
-    let main_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
-        .split(chunks[1]);
+    // Same, leaving out debuffs
+    fn buff_multiplier(&self) -> f64 {
+        self.active_events.iter()
+            .filter(|e| !e.kind.is_debuff())
+            .map(|e| e.kind.production_multiplier())
+            .product()
+    }
 
-    // Tab indicator - full width
-    let current_tab_name = match app.game_state.current_tab {
-        Tab::Passive => "1-Passive Upgrades",
-        Tab::Click => "2-Click Upgrades", 
-        Tab::Achievements => "3-Achievements",
-    };
-    
-    let tab_indicator = Paragraph::new(current_tab_name)
-        .style(Style::default().bg(Color::Blue).fg(Color::White).add_modifier(Modifier::BOLD))
-        .alignment(Alignment::Center);
-    f.render_widget(tab_indicator, main_chunks[0]);
+    fn prestige_multiplier(&self) -> f64 {
+        prestige::points_multiplier(self.prestige_points)
+    }
 
-    // Content based on selected tab - now takes full width
-    match app.game_state.current_tab {
-        Tab::Passive | Tab::Click => {
-            // Split the content area for upgrades
-            let content_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-                .split(main_chunks[1]);
-
-            // Left side - Click area and progress (only show when in click tab or as secondary info)
-            let left_chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Min(5), Constraint::Length(5)].as_ref())
-                .split(content_chunks[0]);
+    fn apply_synergies(&mut self) {
+        for upgrade in &mut self.upgrades {
+            upgrade.synergy_bonus = 0.0;
+        }
+        for synergy in &SYNERGIES {
+            let Some(sources) = self.upgrades.iter().find(|u| u.name == synergy.source).map(|u| u.owned) else {
+                continue;
+            };
+            if let Some(target) = self.upgrades.iter_mut().find(|u| u.name == synergy.target) {
+                target.synergy_bonus += synergy.bonus * sources as f64;
+            }
+        }
+    }
 
-            let click_area = Paragraph::new(vec![
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("CLICK FOR GOLD!", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::raw("Press "),
-                    Span::styled("SPACE", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                    Span::raw(" to mine +"),
-                    Span::styled(GameState::format_number(app.game_state.click_power), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                    Span::raw(" gold (0.5s cooldown)")
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::raw("Or just wait and earn "),
-                    Span::styled(format!("{} gold/sec", GameState::format_number(app.game_state.gold_per_second)), Style::default().fg(Color::Green)),
-                ]),
-            ])
-            .block(Block::default().borders(Borders::ALL).title("Mining"))
-            .alignment(Alignment::Center)
-            .wrap(Wrap { trim: true });
-            f.render_widget(click_area, left_chunks[0]);
+    // Gold per second from passive upgrades with the given achievement multiplier;
+    // everything else (events, prestige) applies as usual
+    fn passive_rate(&self, achievement_multiplier: f64) -> f64 {
+        self.event_multiplier() * self.passive_production(achievement_multiplier)
+    }
 
-            // Progress bar showing gold accumulation
-            let progress = (app.game_state.gold % 100.0) / 100.0;
-            let gauge = Gauge::default()
-                .block(Block::default().borders(Borders::ALL).title("Gold Progress"))
-                .gauge_style(Style::default().fg(Color::Yellow))
-                .percent((progress * 100.0) as u16)
-                .label(format!("{:.1}%", progress * 100.0));
-            f.render_widget(gauge, left_chunks[1]);
+    // Passive rate before any event effects
+    fn passive_production(&self, achievement_multiplier: f64) -> f64 {
+        achievement_multiplier * self.prestige_multiplier() * self.upgrades.iter()
+            .filter(|u| u.upgrade_type == UpgradeType::Passive)
+            .map(|u| u.current_production())
+            .sum::<f64>()
+    }
 
-            // Right side - Upgrades list
-            let current_upgrades = app.game_state.get_current_upgrades();
-            let upgrade_items: Vec<ListItem> = current_upgrades
-                .iter()
-                .enumerate()
-                .map(|(i, upgrade)| {
-                    let cost_color = if upgrade.can_afford(app.game_state.gold) {
-                        Color::Green
-                    } else {
-                        Color::Red
-                    };
+    fn recompute_rates(&mut self) {
+        self.apply_synergies();
+        let prestige_multiplier = self.prestige_multiplier();
 
-                    let style = if i == app.game_state.selected_upgrade {
-                        Style::default().bg(Color::DarkGray)
-                    } else {
-                        Style::default()
-                    };
+        // Rewards add together, so two +5% achievements give +10%
+        self.achievement_multiplier = 1.0 + self.achievements.iter()
+            .filter(|a| a.completed)
+            .map(|a| a.reward - 1.0)
+            .sum::<f64>();
 
-                    let effect_text = match upgrade.upgrade_type {
-                        UpgradeType::Passive => format!("+{}/sec", GameState::format_number(upgrade.base_production)),
-                        UpgradeType::Click => format!("+{}/click", GameState::format_number(upgrade.base_production)),
-                    };
+        self.gold_per_second = self.passive_rate(self.achievement_multiplier).clamp(0.0, GOLD_CAP);
+        self.base_gold_per_second = (self.buff_multiplier() * self.passive_production(self.achievement_multiplier)).min(GOLD_CAP);
+        
+        // Calculate click power from click upgrades
+        self.click_power = (self.achievement_multiplier * prestige_multiplier
+            * prestige::click_multiplier(&self.prestige_upgrades)
+            * (1.0 + self.upgrades.iter()
+                .filter(|u| u.upgrade_type == UpgradeType::Click)
+                .map(|u| u.current_production())
+                .sum::<f64>()))
+            .min(GOLD_CAP);
 
-                    let content = vec![
-                        Line::from(vec![
-                            Span::styled(format!("{} ({})", upgrade.name, upgrade.owned), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-                        ]),
-                        Line::from(vec![
-                            Span::raw("Cost: "),
-                            Span::styled(GameState::format_number(upgrade.current_cost()), Style::default().fg(cost_color)),
-                            Span::raw(" | "),
-                            Span::styled(effect_text, Style::default().fg(Color::Green)),
-                        ]),
-                        Line::from(vec![
-                            Span::styled(upgrade.description.clone(), Style::default().fg(Color::Gray))
-                        ]),
-                    ];
+        let discount = prestige::cost_discount(&self.prestige_upgrades);
+        for upgrade in &mut self.upgrades {
+            upgrade.cost_discount = discount;
+        }
 
-                    ListItem::new(content).style(style)
-                })
-                .collect();
+        self.auto_clicks_per_second = self.upgrades.iter()
+            .filter(|u| u.upgrade_type == UpgradeType::AutoClick)
+            .map(|u| u.current_production())
+            .sum();
+    }
 
-            let upgrades = List::new(upgrade_items)
-                .block(Block::default().borders(Borders::ALL).title(format!("Gold: {} (Up/Down select, Enter buy)", GameState::format_number(app.game_state.gold))))
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
-                .highlight_symbol("> ");
-            f.render_widget(upgrades, content_chunks[1]);
+    fn tick_events(&mut self, delta: f64) {
+        for event in &mut self.active_events {
+            event.remaining -= delta;
         }
+        self.active_events.retain(|e| !e.is_expired());
 
-        Tab::Achievements => {
-            // Split the content area for achievements - same as upgrades
-            let content_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-                .split(main_chunks[1]);
-
-            // Left side - Click area and progress (same as other tabs)
-            let left_chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Min(5), Constraint::Length(5)].as_ref())
-                .split(content_chunks[0]);
+        if self.rng.chance(self.event_chance * delta) {
+            // Cave-ins are the rarer kind, a fifth of events
+            let kind = match self.rng.next_f64() {
+                roll if roll < 0.4 => EventKind::GoldRush,
+                roll if roll < 0.8 => EventKind::LuckyStrike,
+                _ => EventKind::CaveIn,
+            };
+            // Rolling an event that's already running does nothing rather than stacking
+            if !self.active_events.iter().any(|e| e.kind == kind) {
+                let duration = self.event_duration.unwrap_or(kind.duration());
+                self.active_events.push(ActiveEvent::new(kind, duration));
+            }
+        }
+    }
 
-            let click_area = Paragraph::new(vec![
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("CLICK FOR GOLD!", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::raw("Press "),
-                    Span::styled("SPACE", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                    Span::raw(" to mine +"),
-                    Span::styled(GameState::format_number(app.game_state.click_power), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                    Span::raw(" gold (0.5s cooldown)")
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::raw("Or just wait and earn "),
-                    Span::styled(format!("{} gold/sec", GameState::format_number(app.game_state.gold_per_second)), Style::default().fg(Color::Green)),
-                ]),
-            ])
-            .block(Block::default().borders(Borders::ALL).title("Mining"))
-            .alignment(Alignment::Center)
-            .wrap(Wrap { trim: true });
-            f.render_widget(click_area, left_chunks[0]);
+    fn tick_nugget(&mut self, delta: f64) {
+        if let Some(nugget) = &mut self.nugget {
+            nugget.age += delta;
+            if nugget.age >= NUGGET_LIFETIME {
+                self.nugget = None;
+            }
+        } else if self.rng.chance(NUGGET_CHANCE_PER_SECOND * delta) {
+            self.nugget = Some(Nugget { x: self.rng.next_f64(), y: self.rng.next_f64(), age: 0.0 });
+        }
+    }
 
-            // Progress bar showing gold accumulation
-            let progress = (app.game_state.gold % 100.0) / 100.0;
-            let gauge = Gauge::default()
-                .block(Block::default().borders(Borders::ALL).title("Gold Progress"))
-                .gauge_style(Style::default().fg(Color::Yellow))
-                .percent((progress * 100.0) as u16)
-                .label(format!("{:.1}%", progress * 100.0));
-            f.render_widget(gauge, left_chunks[1]);
+    // Picks up the nugget if one is showing, returning the gold it was worth
+    fn collect_nugget(&mut self) -> Option<f64> {
+        if self.paused {
+            return None;
+        }
+        self.nugget.take()?;
+        let reward = (self.gold_per_second * NUGGET_REWARD_SECONDS).max(self.click_power * NUGGET_MIN_CLICKS);
+        self.last_purchase = None;
+        self.earn(reward);
+        Some(reward)
+    }
 
-            // Right side - Achievements list
-            let achievement_items: Vec<ListItem> = app.game_state.achievements
-                .iter()
-                .enumerate()
-                .map(|(i, achievement)| {
-                    let style = if i == app.game_state.selected_upgrade {
-                        Style::default().bg(Color::DarkGray)
-                    } else {
-                        Style::default()
-                    };
+    fn click_for_gold(&mut self) -> Option<ClickOutcome> {
+        self.click_at(self.clock.now())
+    }
 
-                    let status_color = if achievement.completed {
-                        Color::Green
-                    } else {
-                        Color::Yellow
-                    };
+    // Returns what the click earned, or None if it was ignored (paused or cooling down)
+    fn click_at(&mut self, now: Instant) -> Option<ClickOutcome> {
+        if self.paused {
+            return None;
+        }
 
-                    let status_symbol = if achievement.completed { "[DONE]" } else { "[    ]" };
+        if now.duration_since(self.last_click) >= self.click_cooldown {
+            let lucky_strike = self.active_events.iter_mut().find(|e| e.crit_charges > 0);
+            let is_crit = match lucky_strike {
+                Some(event) => {
+                    event.crit_charges -= 1;
+                    true
+                }
+                None => self.rng.chance(self.crit_chance),
+            };
 
-                    let current_value = match achievement.achievement_type {
-                        AchievementType::TotalGold(_) => GameState::format_number(app.game_state.total_gold_earned),
-                        AchievementType::GoldPerSecond(_) => GameState::format_number(app.game_state.gold_per_second),
-                        AchievementType::TotalClicks(_) => app.game_state.total_clicks.to_string(),
-                        AchievementType::ClickPower(_) => GameState::format_number(app.game_state.click_power),
-                        AchievementType::UpgradesPurchased(_) => app.game_state.total_upgrades_purchased.to_string(),
-                    };
+            if self.total_clicks > 0 && now.duration_since(self.last_combo_time) <= COMBO_WINDOW {
+                self.combo = (self.combo + 1).min(MAX_COMBO);
+            } else {
+                self.combo = 0;
+            }
+            self.last_combo_time = now;
 
-                    let content = vec![
-                        Line::from(vec![
-                            Span::styled(format!("{} {}", status_symbol, achievement.name), Style::default().fg(status_color).add_modifier(Modifier::BOLD))
-                        ]),
-                        Line::from(vec![
-                            Span::styled(achievement.description.clone(), Style::default().fg(Color::Gray))
-                        ]),
-                        Line::from(vec![
-                            Span::raw("Progress: "),
-                            Span::styled(current_value, Style::default().fg(Color::Cyan)),
-                            Span::raw(" / "),
-                            Span::styled(GameState::format_number(achievement.target), Style::default().fg(Color::White)),
-                        ]),
-                    ];
+            let mut gain = self.click_power * self.combo_multiplier();
+            if is_crit {
+                gain *= self.crit_multiplier;
+            }
+            self.last_purchase = None;
+            self.earn(gain);
+            self.total_clicks += 1;
+            self.last_click = now;
+            self.recent_clicks.push_back(now);
+            while self.recent_clicks.front().is_some_and(|&at| now.duration_since(at) > CLICK_RATE_WINDOW) {
+                self.recent_clicks.pop_front();
+            }
+            return Some(ClickOutcome { gold: gain, crit: is_crit });
+        }
+        None
+    }
 
-                    ListItem::new(content).style(style)
-                })
-                .collect();
+    fn time_since_last_click(&self) -> Duration {
+        self.clock.now().duration_since(self.last_click)
+    }
 
-            let completed_count = app.game_state.achievements.iter().filter(|a| a.completed).count();
-            let total_count = app.game_state.achievements.len();
+    fn click_cooldown_remaining(&self) -> Duration {
+        self.click_cooldown.saturating_sub(self.time_since_last_click())
+    }
 
-            let achievements = List::new(achievement_items)
-                .block(Block::default().borders(Borders::ALL).title(format!("Long-term Goals ({}/{})", completed_count, total_count)))
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
-                .highlight_symbol("> ");
-            f.render_widget(achievements, content_chunks[1]);
+    // Average gold per click once crits are averaged in (combos aside); while Lucky Strike
+    // has charges left every click crits
+    fn expected_click_value(&self) -> f64 {
+        if self.active_events.iter().any(|e| e.crit_charges > 0) {
+            return self.click_power * self.crit_multiplier;
         }
+        self.click_power * (1.0 + self.crit_chance * (self.crit_multiplier - 1.0))
     }
 
-    // Footer
-    let footer_text = if app.game_state.show_help {
-        "SPACE: Mine gold | Up/Down: Select | ENTER: Buy | 1: Passive | 2: Click | 3: Achievements | H: Toggle help | Q: Quit"
-    } else {
-        "Press H for help | 1-3: Switch tabs | Q to quit"
-    };
+    fn hand_clicks_per_second(&self) -> f64 {
+        let now = self.clock.now();
+        let clicks = self.recent_clicks.iter().filter(|&&at| now.duration_since(at) <= CLICK_RATE_WINDOW).count();
+        clicks as f64 / CLICK_RATE_WINDOW.as_secs_f64()
+    }
 
-    let footer = Paragraph::new(footer_text)
-        .block(Block::default().borders(Borders::ALL).title("Controls"))
-        .alignment(Alignment::Center);
-    f.render_widget(footer, chunks[2]);
-}
+    // Gold per second from clicking by hand, estimated from the recent click rate
+    fn active_income(&self) -> f64 {
+        self.hand_clicks_per_second() * self.expected_click_value()
+    }
 
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
-    let mut update_interval = interval(Duration::from_millis(100));
-    update_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // Gold/sec one more copy of `upgrade` would add, events and crits aside. Click power
+    // counts at the recent hand click rate plus auto-clickers, so it's worth nothing to
+    // someone who doesn't click
+    fn marginal_income(&self, upgrade: &Upgrade) -> f64 {
+        let multiplier = self.achievement_multiplier * self.prestige_multiplier();
+        match upgrade.upgrade_type {
+            UpgradeType::Passive => upgrade.unit_production() * multiplier,
+            UpgradeType::Click => upgrade.unit_production() * multiplier * prestige::click_multiplier(&self.prestige_upgrades)
+                * (self.hand_clicks_per_second() + self.auto_clicks_per_second),
+            UpgradeType::AutoClick => upgrade.unit_production() * self.click_power,
+        }
+    }
 
-    loop {
-        terminal.draw(|f| ui(f, &app))?;
+    /// Selects the unlocked upgrade on either upgrade tab that adds the most gold/sec per
+    /// gold spent, switching tabs (and dropping a filter that hides it) as needed. Returns
+    /// its name, or None when no upgrade would add anything yet.
+    fn select_recommended(&mut self) -> Option<String> {
+        let (name, upgrade_type) = self.upgrades.iter()
+            .filter(|u| self.is_unlocked(u))
+            .map(|u| (u, self.marginal_income(u) / u.current_cost()))
+            .filter(|(_, value)| *value > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(u, _)| (u.name.clone(), u.upgrade_type.clone()))?;
 
-        tokio::select! {
-            _ = update_interval.tick() => {
-                app.on_tick();
-                if app.should_quit {
-                    return Ok(());
-                }
+        self.switch_tab(if upgrade_type == UpgradeType::Passive { Tab::Passive } else { Tab::Click });
+        let position = |state: &Self| state.get_current_upgrades().iter()
+            .position(|u| u.name == name && u.upgrade_type == upgrade_type);
+        let index = match position(self) {
+            Some(index) => index,
+            None => {
+                self.filter.clear();
+                position(self)?
             }
+        };
+        self.selected_upgrade = index;
+        Some(name)
+    }
+
+    fn combo_multiplier(&self) -> f64 {
+        1.0 + COMBO_STEP_BONUS * self.combo as f64
+    }
+
+    /// One line for sharing a score. Tools may parse this, so keep the layout fixed:
+    /// `TGM: <gold> gold, <n> upgrades, <done>/<total> achievements, <h>h<mm>m, prestige <n>`,
+    /// where gold is what this run earned, in short suffixes with one decimal.
+    fn share_summary(&self) -> String {
+        let minutes = (self.play_time / 60.0) as u64;
+        format!(
+            "TGM: {} gold, {} upgrades, {}/{} achievements, {}h{:02}m, prestige {}",
+            Self::format_number(self.total_gold_earned, NumberFormat::Short, 1),
+            self.total_upgrades_purchased,
+            self.achievements.iter().filter(|a| a.completed).count(),
+            self.achievements.len(),
+            minutes / 60,
+            minutes % 60,
+            self.prestige_count,
+        )
+    }
+
+    // Gold on hand plus what every owned upgrade would cost to rebuy
+    fn net_worth(&self) -> f64 {
+        self.gold + self.upgrades.iter().map(Upgrade::rebuy_value).sum::<f64>()
+    }
+
+    // Owned passive upgrades with the gold/sec each adds (global multipliers included) and
+    // their share of the total, biggest contributor first
+    fn passive_breakdown(&self) -> Vec<(&Upgrade, f64, f64)> {
+        let mut owned: Vec<&Upgrade> = self.upgrades.iter()
+            .filter(|u| u.upgrade_type == UpgradeType::Passive && u.owned > 0)
+            .collect();
+        let raw_total: f64 = owned.iter().map(|u| u.current_production()).sum();
+        if raw_total <= 0.0 {
+            return Vec::new();
+        }
+
+        owned.sort_by(|a, b| b.current_production().total_cmp(&a.current_production()));
+        owned.into_iter()
+            .map(|u| {
+                let share = u.current_production() / raw_total;
+                (u, share * self.gold_per_second, share)
+            })
+            .collect()
+    }
+
+    fn get_current_upgrades(&self) -> Vec<&Upgrade> {
+        let mut upgrades: Vec<&Upgrade> = match self.current_tab {
+            Tab::Passive => self.upgrades.iter().filter(|u| u.upgrade_type == UpgradeType::Passive).collect(),
+            Tab::Click => self.upgrades.iter()
+                .filter(|u| matches!(u.upgrade_type, UpgradeType::Click | UpgradeType::AutoClick))
+                .collect(),
+            Tab::Achievements | Tab::Stats | Tab::Prestige => Vec::new(),
+        };
+
+        if !self.filter.is_empty() {
+            let query = self.filter.to_lowercase();
+            upgrades.retain(|u| u.name.to_lowercase().contains(&query) || u.description.to_lowercase().contains(&query));
+        }
+
+        // Stable sorts, so ties keep the default order
+        match self.sort_mode {
+            SortMode::Default => {}
+            SortMode::Cost => upgrades.sort_by(|a, b| a.current_cost().total_cmp(&b.current_cost())),
+            SortMode::Roi => upgrades.sort_by(|a, b| {
+                let roi_a = a.unit_production() / a.current_cost();
+                let roi_b = b.unit_production() / b.current_cost();
+                roi_b.total_cmp(&roi_a)
+            }),
+        }
+
+        upgrades
+    }
+
+    fn is_unlocked(&self, upgrade: &Upgrade) -> bool {
+        upgrade.owned > 0 || match &upgrade.unlock_condition {
+            None => true,
+            Some(UnlockCondition::Owned(name, count)) => self.upgrades.iter().any(|u| u.name == *name && u.owned >= *count),
+            Some(UnlockCondition::TotalGold(gold)) => self.total_gold_earned >= *gold,
+        }
+    }
+
+    // Affordable and unlocked; every way of buying goes through this
+    fn can_buy(&self, upgrade: &Upgrade) -> bool {
+        self.is_unlocked(upgrade) && upgrade.can_afford(self.gold)
+    }
+
+    // Cheapest unlocked upgrade on the current tab we can't afford yet; tabs without an
+    // upgrade list look at every upgrade
+    fn next_purchase_goal(&self) -> Option<&Upgrade> {
+        let candidates = match self.current_tab {
+            Tab::Passive | Tab::Click => self.get_current_upgrades(),
+            Tab::Achievements | Tab::Stats | Tab::Prestige => self.upgrades.iter().collect(),
+        };
+        candidates.into_iter()
+            .filter(|u| self.is_unlocked(u) && !u.can_afford(self.gold))
+            .min_by(|a, b| a.current_cost().total_cmp(&b.current_cost()))
+    }
+
+    // Returns whether anything was bought
+    fn buy_selected(&mut self) -> bool {
+        match self.current_tab {
+            Tab::Achievements | Tab::Stats => return false,
+            Tab::Prestige => return self.buy_prestige_upgrade(self.selected_upgrade),
+            Tab::Passive | Tab::Click => {}
+        }
+
+        let current_upgrades = self.get_current_upgrades();
+        if let Some(&upgrade) = current_upgrades.get(self.selected_upgrade)
+            && self.can_buy(upgrade)
+        {
+            let upgrade_index = self.upgrades.iter().position(|u| 
+                u.name == upgrade.name && u.upgrade_type == upgrade.upgrade_type
+            ).unwrap();
             
-            event = tokio::task::spawn_blocking(|| {
-                if event::poll(Duration::from_millis(0)).unwrap_or(false) {
-                    event::read().ok()
-                } else {
-                    None
-                }
-            }) => {
-                if let Ok(Some(event)) = event {
-                    if let Event::Key(key) = event {
-                        if key.kind == KeyEventKind::Press {
-                            app.on_key(key.code);
-                            if app.should_quit {
-                                return Ok(());
-                            }
-                        }
-                    }
+            let max_owned = self.upgrades[upgrade_index].max_owned;
+            let cost = self.purchase(upgrade_index);
+            self.last_purchase = Some(LastPurchase { items: vec![(upgrade_index, 1, cost, max_owned)], at: self.clock.now() });
+            return true;
+        }
+        false
+    }
+
+    // Buys one copy of `self.upgrades[index]`, returning its price; callers check affordability
+    fn purchase(&mut self, index: usize) -> f64 {
+        let cost = self.upgrades[index].purchase();
+        self.gold -= cost;
+        self.total_gold_spent += cost;
+        self.total_upgrades_purchased += 1;
+        cost
+    }
+
+    // Sells one copy of the selected upgrade and returns the refund. Lifetime counters like
+    // total_upgrades_purchased keep counting the sold copy
+    fn sell_selected(&mut self) -> Option<f64> {
+        let (name, upgrade_type) = match self.current_tab {
+            Tab::Passive | Tab::Click => self.get_current_upgrades().get(self.selected_upgrade)
+                .filter(|u| u.owned > 0)
+                .map(|u| (u.name.clone(), u.upgrade_type.clone()))?,
+            Tab::Achievements | Tab::Stats | Tab::Prestige => return None,
+        };
+        let upgrade = self.upgrades.iter_mut().find(|u| u.name == name && u.upgrade_type == upgrade_type)?;
+        let refund = upgrade.sell();
+        // Otherwise auto-buy would buy it straight back at full price
+        upgrade.auto_buy = false;
+        // A refund isn't income, so it doesn't count toward gold earned
+        self.gold = (self.gold + refund).min(GOLD_CAP);
+        self.last_purchase = None;
+        self.recompute_rates();
+        Some(refund)
+    }
+
+    // Buys `count` copies at once, returning the total price; callers check `max_affordable`
+    fn purchase_many(&mut self, index: usize, count: u64) -> f64 {
+        let upgrade = &mut self.upgrades[index];
+        let cost = upgrade.bulk_cost(count);
+        self.gold -= cost;
+        self.total_gold_spent += cost;
+        upgrade.owned += count;
+        upgrade.max_owned = upgrade.max_owned.max(upgrade.owned);
+        self.total_upgrades_purchased += count;
+        cost
+    }
+
+    /// Buys the selected upgrade up to its next milestone, or as many copies toward it as
+    /// gold allows. Returns how many were bought.
+    fn buy_to_milestone(&mut self) -> u64 {
+        if !matches!(self.current_tab, Tab::Passive | Tab::Click) {
+            return 0;
+        }
+        let Some(index) = self.get_current_upgrades().get(self.selected_upgrade)
+            .and_then(|selected| self.upgrades.iter().position(|u| u.name == selected.name && u.upgrade_type == selected.upgrade_type))
+        else {
+            return 0;
+        };
+
+        let upgrade = &self.upgrades[index];
+        if !self.is_unlocked(upgrade) {
+            return 0;
+        }
+        let count = upgrade.copies_to_milestone().min(upgrade.max_affordable(self.gold));
+        if count == 0 {
+            return 0;
+        }
+        let max_owned = upgrade.max_owned;
+        let cost = self.purchase_many(index, count);
+        self.last_purchase = Some(LastPurchase { items: vec![(index, count, cost, max_owned)], at: self.clock.now() });
+        count
+    }
+
+    /// Takes back the latest buy action (a single purchase or a whole buy-all), refunding
+    /// exactly what it cost. Only possible within UNDO_WINDOW and before any click, nugget
+    /// or other purchase; passive income earned in the meantime is kept. Returns how many
+    /// copies were returned.
+    fn undo_purchase(&mut self) -> Option<u64> {
+        let last = self.last_purchase.take()?;
+        if self.clock.now().duration_since(last.at) > UNDO_WINDOW {
+            return None;
+        }
+
+        let mut returned = 0;
+        for (index, count, cost, max_owned) in last.items {
+            self.upgrades[index].owned -= count;
+            self.upgrades[index].max_owned = max_owned;
+            self.gold = (self.gold + cost).min(GOLD_CAP);
+            self.total_gold_spent -= cost;
+            self.total_upgrades_purchased -= count;
+            returned += count;
+        }
+        self.recompute_rates();
+        Some(returned)
+    }
+
+    /// Spends gold on the current tab's upgrades by repeatedly buying the cheapest affordable
+    /// one, returning how many copies were bought. Runs of copies that stay cheaper than
+    /// every other option are bought in one step, so large balances don't loop per copy.
+    fn buy_all_affordable(&mut self) -> u64 {
+        if !matches!(self.current_tab, Tab::Passive | Tab::Click) {
+            return 0;
+        }
+
+        let candidates: Vec<usize> = self.get_current_upgrades().iter()
+            .filter_map(|upgrade| self.upgrades.iter().position(|u|
+                u.name == upgrade.name && u.upgrade_type == upgrade.upgrade_type
+            ))
+            .collect();
+        let cost_of = |state: &Self, index: usize| state.upgrades[index].current_cost();
+
+        let mut bought = 0;
+        let mut items: Vec<(usize, u64, f64, u64)> = Vec::new();
+        // Ties go to the first candidate, matching `min_by`. Locks are checked every step
+        // since buying can unlock more upgrades.
+        while let Some(cheapest) = candidates.iter().copied()
+            .filter(|&index| self.is_unlocked(&self.upgrades[index]))
+            .min_by(|&a, &b| cost_of(self, a).total_cmp(&cost_of(self, b)))
+        {
+            let upgrade = &self.upgrades[cheapest];
+            if !upgrade.can_afford(self.gold) {
+                break;
+            }
+
+            // Locked upgrades count here too, so a run stops where one might take over
+            let runner_up = candidates.iter().copied()
+                .filter(|&index| index != cheapest)
+                .map(|index| cost_of(self, index))
+                .min_by(f64::total_cmp);
+            let count = upgrade.max_affordable(self.gold)
+                .min(runner_up.map_or(u64::MAX, |limit| upgrade.copies_below(limit)))
+                .max(1);
+            let max_owned = upgrade.max_owned;
+            let cost = self.purchase_many(cheapest, count);
+            match items.iter_mut().find(|(index, _, _, _)| *index == cheapest) {
+                Some(item) => {
+                    item.1 += count;
+                    item.2 += cost;
                 }
+                None => items.push((cheapest, count, cost, max_owned)),
             }
+            bought += count;
+        }
+        if !items.is_empty() {
+            self.last_purchase = Some(LastPurchase { items, at: self.clock.now() });
         }
+        bought
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Buys the cheapest affordable upgrade of any type, returning whether anything was bought
+    fn buy_cheapest(&mut self) -> bool {
+        let cheapest = self.upgrades.iter()
+            .enumerate()
+            .filter(|(_, u)| self.can_buy(u))
+            .min_by(|(_, a), (_, b)| a.current_cost().total_cmp(&b.current_cost()))
+            .map(|(index, _)| index);
 
-    // Create app and run it
-    let app = App::new();
-    let res = run_app(&mut terminal, app).await;
-
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+        match cheapest {
+            Some(index) => {
+                self.purchase(index);
+                true
+            }
+            None => false,
+        }
+    }
 
-    if let Err(err) = res {
-        println!("{:?}", err);
+    // Achievements as listed on their tab
+    fn visible_achievements(&self) -> Vec<&Achievement> {
+        self.achievements.iter().filter(|a| !(self.hide_completed && a.completed)).collect()
+    }
+
+    // Number of selectable rows on the current tab
+    fn selection_len(&self) -> usize {
+        match self.current_tab {
+            Tab::Passive | Tab::Click => self.get_current_upgrades().len(),
+            Tab::Achievements => self.visible_achievements().len(),
+            Tab::Stats => 0,
+            Tab::Prestige => self.prestige_upgrades.len(),
+        }
+    }
+
+    fn select_next(&mut self) {
+        let last = self.selection_len().saturating_sub(1);
+        if self.wrap_selection && self.selected_upgrade >= last {
+            self.selected_upgrade = 0;
+        } else {
+            self.select_down_by(1);
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if self.wrap_selection && self.selected_upgrade == 0 {
+            self.selected_upgrade = self.selection_len().saturating_sub(1);
+        } else {
+            self.select_up_by(1);
+        }
+    }
+
+    fn select_down_by(&mut self, rows: usize) {
+        let last = self.selection_len().saturating_sub(1);
+        self.selected_upgrade = (self.selected_upgrade + rows).min(last);
+    }
+
+    fn select_up_by(&mut self, rows: usize) {
+        self.selected_upgrade = self.selected_upgrade.saturating_sub(rows);
+    }
+
+    // Moves the selection to the first row (as displayed, so after sorting and filtering)
+    // that can be bought right now; returns false and leaves it alone if there's none
+    fn select_first_affordable(&mut self) -> bool {
+        let index = match self.current_tab {
+            Tab::Passive | Tab::Click => self.get_current_upgrades().iter().position(|u| self.can_buy(u)),
+            Tab::Prestige => self.prestige_upgrades.iter().position(|u| u.can_afford(self.prestige_points)),
+            Tab::Achievements | Tab::Stats => None,
+        };
+        match index {
+            Some(index) => {
+                self.selected_upgrade = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Keeps the selection inside the list after it shrinks (e.g. when filtering)
+    fn clamp_selection(&mut self) {
+        self.selected_upgrade = self.selected_upgrade.min(self.selection_len().saturating_sub(1));
+    }
+
+    fn switch_tab(&mut self, tab: Tab) {
+        if self.current_tab != tab {
+            self.tab_selections[tab_index(&self.current_tab)] = self.selected_upgrade;
+            self.selected_upgrade = self.tab_selections[tab_index(&tab)];
+            self.current_tab = tab;
+            // The list may have shrunk since, e.g. a new filter
+            self.clamp_selection();
+        }
     }
 
-    Ok(())
+    fn format_number(num: f64, format: NumberFormat, precision: usize) -> String {
+        if num >= GOLD_CAP {
+            return "MAX".to_string();
+        }
+        if num.abs() < 1_000.0 {
+            return format!("{:.*}", precision, num);
+        }
+
+        match format {
+            NumberFormat::Short => {
+                let mut tier = 0;
+                let mut scaled = num;
+                while scaled.abs() >= 1_000.0 {
+                    scaled /= 1_000.0;
+                    tier += 1;
+                }
+                // 999.996K would print as "1000.00K", so carry into the next suffix
+                let unit = 10f64.powi(precision as i32);
+                if (scaled * unit).round().abs() >= 1_000.0 * unit {
+                    scaled /= 1_000.0;
+                    tier += 1;
+                }
+                format!("{:.*}{}", precision, scaled, Self::number_suffix(tier))
+            }
+            NumberFormat::Scientific => format!("{:.*e}", precision, num),
+            NumberFormat::Grouped if num.abs() >= GROUPED_DIGITS_LIMIT => format!("{:.*e}", precision, num),
+            NumberFormat::Grouped => {
+                let plain = format!("{:.*}", precision, num.abs());
+                let (whole, fraction) = match plain.split_once('.') {
+                    Some((whole, fraction)) => (whole, format!(".{}", fraction)),
+                    None => (plain.as_str(), String::new()),
+                };
+                let mut grouped = String::new();
+                for (index, digit) in whole.chars().enumerate() {
+                    if index > 0 && (whole.len() - index) % 3 == 0 {
+                        grouped.push(',');
+                    }
+                    grouped.push(digit);
+                }
+                format!("{}{}{}", if num < 0.0 { "-" } else { "" }, grouped, fraction)
+            }
+        }
+    }
+
+    // "", K, M, B, T, then aa, ab, ... az, ba, ... for each further power of 1,000
+    fn number_suffix(tier: usize) -> String {
+        const NAMED: [&str; 5] = ["", "K", "M", "B", "T"];
+        if tier < NAMED.len() {
+            return NAMED[tier].to_string();
+        }
+        let index = tier - NAMED.len();
+        let first = (b'a' + (index / 26 % 26) as u8) as char;
+        let second = (b'a' + (index % 26) as u8) as char;
+        format!("{}{}", first, second)
+    }
+
+    fn format(&self, num: f64) -> String {
+        Self::format_number(num, self.number_format, self.precision)
+    }
+
+    // Reads an amount typed by the player: plain ("2500"), scientific ("2.5e3") or with one
+    // of the named suffixes ("2.5K"), case-insensitively
+    fn parse_amount(text: &str) -> Option<f64> {
+        let text = text.trim();
+        let (number, scale) = match text.char_indices().last()? {
+            (index, suffix) if suffix.is_ascii_alphabetic() => {
+                let tier = ["K", "M", "B", "T"].iter().position(|s| s.eq_ignore_ascii_case(&suffix.to_string()))?;
+                (&text[..index], 1_000f64.powi(tier as i32 + 1))
+            }
+            _ => (text, 1.0),
+        };
+        let amount = number.trim().parse::<f64>().ok()? * scale;
+        (amount.is_finite() && amount > 0.0).then_some(amount.min(GOLD_CAP))
+    }
+}
+
+fn new_game(difficulty: Difficulty, seed: Option<u64>) -> GameState {
+    let mut state = GameState::default();
+    state.set_difficulty(difficulty);
+    if let Some(seed) = seed {
+        state.seed = seed;
+        state.rng = Rng::new(seed);
+    }
+    state
+}
+
+// Pane that Enter (and, on the tab bar, Left/Right) acts on; Tab and Shift+Tab move it
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Focus {
+    Tabs,
+    Mining,
+    List,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::Tabs => Focus::Mining,
+            Focus::Mining => Focus::List,
+            Focus::List => Focus::Tabs,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Focus::Tabs => Focus::List,
+            Focus::Mining => Focus::Tabs,
+            Focus::List => Focus::Mining,
+        }
+    }
+}
+
+// Modal dialogs drawn over the main UI; while one is open it receives all key presses
+enum Overlay {
+    ConfirmReset,
+    ConfirmPrestige,
+    ConfirmQuit,
+    Export(String),
+    // One-line score summary for sharing
+    Share(String),
+    Import { input: String, error: Option<String> },
+    // Everything about one upgrade, by name, for the selected row
+    Details(String),
+    // Prompt for a gold goal; `error` is set when the last entry didn't parse
+    SetGoal { input: String, error: Option<String> },
+    // "While you were away" summary and daily streak; the game clock waits until it's dismissed
+    Welcome { offline: Option<OfflineReport>, daily: Option<DailyBonus> },
+    // Startup menu of saves to play; nothing is loaded until one is picked
+    Profiles { names: Vec<String>, selected: usize },
+    // Read-only text that scrolls with Up/Down, `scroll` lines down
+    Document { document: Document, scroll: u16 },
+}
+
+#[derive(Clone, Copy)]
+enum Document {
+    // Embedded changelog
+    News,
+    PrestigeHistory,
+}
+
+struct App {
+    game_state: GameState,
+    list_state: ListState,
+    should_quit: bool,
+    save_path: PathBuf,
+    // Set when the existing save couldn't be loaded, so we never overwrite it
+    save_blocked: Option<String>,
+    // Problems worth keeping, printed to stderr once the terminal is back to normal
+    notices: Vec<String>,
+    last_save: Instant,
+    toast: Option<(String, Instant)>,
+    theme: Theme,
+    // --theme was given, so the preferences file doesn't pick the theme
+    theme_from_flag: bool,
+    // Display settings from preferences.json, written back to preferences_path when changed
+    preferences: Preferences,
+    preferences_path: Option<PathBuf>,
+    overlay: Option<Overlay>,
+    focus: Focus,
+    editing_filter: bool,
+    // Corner box with unrounded values, for checking balance math
+    show_precise: bool,
+    // Something visible changed since the last draw
+    dirty: bool,
+    // Where the last frame drew clickable things, for mouse hit-testing
+    mining_area: Rect,
+    nugget_area: Rect,
+    list_area: Rect,
+    list_row_heights: Vec<u16>,
+    // Unlocked achievement names waiting for their banner, and the one showing now
+    achievement_queue: VecDeque<String>,
+    achievement_banner: Option<(String, Instant)>,
+    // milestone_values as of the last tick; a celebration fires when a value crosses its
+    // threshold between two ticks, so it can't fire twice for one crossing
+    milestone_values: [f64; CELEBRATIONS.len()],
+    celebration_queue: VecDeque<&'static str>,
+    celebration: Option<(&'static str, Instant)>,
+    keymap: Keymap,
+    // Command-line overrides reapplied to every game we load, reset or import
+    click_cooldown: Option<Duration>,
+    max_timer_step: Option<f64>,
+    event_chance: Option<f64>,
+    event_duration: Option<f64>,
+    precision: Option<usize>,
+    wrap_selection: bool,
+    // --start-tab: shown when a save is opened instead of Passive Upgrades
+    start_tab: Option<Tab>,
+    // --whole-gold-above: header gold drops its decimals from this amount up
+    whole_gold_above: Option<f64>,
+    // Achievement definitions in play (built-in or from achievements.json)
+    achievements: Vec<Achievement>,
+    // Difficulty for games started here (first launch or reset)
+    difficulty: Difficulty,
+    // Whether --difficulty was given, to flag saves that were started on another one
+    difficulty_from_flag: bool,
+    // --seed for games started here; they pick their own otherwise
+    seed: Option<u64>,
+    start_gold: Option<f64>,
+    offline_cap: Duration,
+    // Cheat keys for testing, only with --debug
+    debug: bool,
+    // Frame and tick timings, measured only with --debug
+    perf: Option<PerfStats>,
+    // --confirm-quit: ask before quitting
+    confirm_quit: bool,
+    // Recent click gains drawn as "+10" text floating over the mining panel
+    floating_gains: VecDeque<(ClickOutcome, Instant)>,
+    // --bell: ring the terminal bell on purchases and achievement unlocks
+    bell: bool,
+    bell_pending: bool,
+    // --compact: always use the single-pane layout
+    compact: bool,
+    // --banner: ASCII-art title when the terminal is tall enough
+    banner: bool,
+    // --accessible: mark prices with a check or cross as well as color
+    accessible: bool,
+    // Shortest gap between two draws (--max-fps)
+    min_frame_time: Duration,
+    // Gap between game updates (--tick-ms), independent of drawing
+    tick_interval: Duration,
+    // Page of the first-run tutorial being shown, if any
+    tutorial_step: Option<usize>,
+    // Gold Progress gauge fill (0..1), eased toward the real value each tick
+    displayed_progress: f64,
+    progress_updated_at: Instant,
+    // Header gold, counting up smoothly toward the real amount
+    displayed_gold: f64,
+    gold_updated_at: Instant,
+}
+
+// How long the last draw and tick took and when recent frames were drawn, for the
+// --debug corner box
+#[derive(Default)]
+struct PerfStats {
+    draw_time: Duration,
+    tick_time: Duration,
+    // Draws within the last second, oldest first
+    recent_draws: VecDeque<Instant>,
+}
+
+impl PerfStats {
+    fn record_draw(&mut self, started: Instant) {
+        let now = Instant::now();
+        self.draw_time = now - started;
+        self.recent_draws.push_back(now);
+        while self.recent_draws.front().is_some_and(|&at| now - at > Duration::from_secs(1)) {
+            self.recent_draws.pop_front();
+        }
+    }
+
+    fn record_tick(&mut self, started: Instant) {
+        self.tick_time = started.elapsed();
+    }
+
+    // Frames actually drawn in the last second
+    fn fps(&self) -> usize {
+        self.recent_draws.iter().filter(|at| at.elapsed() <= Duration::from_secs(1)).count()
+    }
+}
+
+// Longest absence that still earns offline gold, unless --offline-cap-hours says otherwise
+const DEFAULT_OFFLINE_CAP: Duration = Duration::from_secs(8 * 60 * 60);
+const WELCOME_MIN_AWAY: Duration = Duration::from_secs(60);
+
+impl App {
+    /// Loads `save_path`, or with `profiles` to choose from, opens the profile menu first
+    /// and loads whichever the player picks ("default" being `save_path`).
+    fn new(save_path: PathBuf, profiles: Vec<String>, options: &cli::Options, keymap: Keymap, achievements: Vec<Achievement>) -> Self {
+        let difficulty = options.difficulty.unwrap_or_default();
+        let mut app = Self {
+            game_state: GameState::default(),
+            list_state: ListState::default().with_selected(Some(0)),
+            should_quit: false,
+            save_path: save_path.clone(),
+            save_blocked: None,
+            notices: Vec::new(),
+            last_save: Instant::now(),
+            toast: None,
+            theme: options.theme.unwrap_or_default().with_color(theme::color_enabled()),
+            theme_from_flag: options.theme.is_some(),
+            preferences: Preferences::default(),
+            preferences_path: None,
+            overlay: None,
+            focus: Focus::List,
+            editing_filter: false,
+            show_precise: false,
+            dirty: true,
+            mining_area: Rect::default(),
+            nugget_area: Rect::default(),
+            list_area: Rect::default(),
+            list_row_heights: Vec::new(),
+            achievement_queue: VecDeque::new(),
+            achievement_banner: None,
+            milestone_values: [0.0; CELEBRATIONS.len()],
+            celebration_queue: VecDeque::new(),
+            celebration: None,
+            keymap,
+            click_cooldown: options.click_cooldown,
+            max_timer_step: options.max_timer_step,
+            event_chance: options.event_chance,
+            event_duration: options.event_duration,
+            precision: options.precision,
+            wrap_selection: options.wrap_selection,
+            start_tab: options.start_tab,
+            whole_gold_above: options.whole_gold_above,
+            achievements,
+            difficulty,
+            difficulty_from_flag: options.difficulty.is_some(),
+            seed: options.seed,
+            start_gold: options.start_gold,
+            offline_cap: options.offline_cap.unwrap_or(DEFAULT_OFFLINE_CAP),
+            debug: options.debug,
+            perf: options.debug.then(PerfStats::default),
+            confirm_quit: options.confirm_quit,
+            floating_gains: VecDeque::with_capacity(FLOATING_GAIN_CAPACITY),
+            bell: options.bell,
+            bell_pending: false,
+            compact: options.compact,
+            banner: options.banner,
+            accessible: options.accessible,
+            tutorial_step: None,
+            min_frame_time: Duration::from_secs_f64(1.0 / options.max_fps.unwrap_or(DEFAULT_MAX_FPS) as f64),
+            tick_interval: options.tick_interval.unwrap_or(DEFAULT_TICK_INTERVAL),
+            displayed_progress: 0.0,
+            progress_updated_at: Instant::now(),
+            displayed_gold: 0.0,
+            gold_updated_at: Instant::now(),
+        };
+        app.replace_game_state(new_game(difficulty, app.seed));
+
+        if profiles.is_empty() {
+            app.open_save(save_path);
+        } else {
+            let names = std::iter::once("default".to_string()).chain(profiles).collect();
+            app.overlay = Some(Overlay::Profiles { names, selected: 0 });
+        }
+        app
+    }
+
+    // Starts playing the save at `save_path`: a fresh game if there's none yet, with
+    // offline earnings paid out for one that exists
+    fn open_save(&mut self, save_path: PathBuf) {
+        let mut backup = None;
+        let loaded = save::load_game(&save_path);
+        let existing = loaded.is_ok();
+        let (game_state, save_blocked) = match loaded {
+            Ok(game_state) => (game_state, None),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (new_game(self.difficulty, self.seed), None),
+            // A damaged save is moved aside rather than overwritten, so it can be recovered by hand
+            Err(err) if save::is_corrupt(&err) => match save::back_up_corrupt_save(&save_path) {
+                Ok(path) => {
+                    self.notices.push(format!(
+                        "warning: {} was corrupt ({}); it was moved to {} and a new game started",
+                        save_path.display(), err, path.display()
+                    ));
+                    backup = Some(path);
+                    (new_game(self.difficulty, self.seed), None)
+                }
+                Err(backup_err) => (new_game(self.difficulty, self.seed), Some(format!(
+                    "{} is corrupt ({}) and couldn't be backed up: {}", save_path.display(), err, backup_err
+                ))),
+            },
+            Err(err) => (new_game(self.difficulty, self.seed), Some(format!("Couldn't load {}: {}", save_path.display(), err))),
+        };
+        if let Some(reason) = &save_blocked {
+            self.show_toast(format!("{} (saving disabled)", reason));
+        } else if let Some(path) = &backup {
+            self.show_toast(format!("Save was corrupt; kept a copy at {} and started over", path.display()));
+        } else if self.difficulty_from_flag && game_state.difficulty != self.difficulty {
+            self.show_toast(format!("This save is on {} difficulty; --difficulty only applies to new games", game_state.difficulty.label()));
+        } else if self.seed.is_some_and(|seed| seed != game_state.seed) {
+            self.show_toast(format!("This save uses seed {}; --seed only applies to new games", game_state.seed));
+        } else if existing && self.start_gold.is_some() {
+            self.show_toast("--start-gold only applies to new games".to_string());
+        }
+        self.save_path = save_path;
+        self.save_blocked = save_blocked;
+        self.last_save = Instant::now();
+
+        self.replace_game_state(game_state);
+        if !existing && let Some(gold) = self.start_gold {
+            self.game_state.gold = gold;
+        }
+        if let Some(tab) = self.start_tab {
+            self.switch_tab(tab);
+        }
+
+        if self.game_state.paused && self.toast.is_none() {
+            self.show_toast("Still paused from last time; press P to resume".to_string());
+        }
+        self.tutorial_step = (!self.game_state.tutorial_seen).then_some(0);
+        // Quick restarts still get paid, just without interrupting the player
+        let offline = self.game_state.collect_offline_earnings(self.offline_cap)
+            .filter(|report| report.away >= WELCOME_MIN_AWAY);
+        let daily = self.game_state.check_in(clock::local_day(self.game_state.clock.unix_time()));
+        if offline.is_some() || daily.is_some() {
+            self.overlay = Some(Overlay::Welcome { offline, daily });
+        }
+    }
+
+    fn replace_game_state(&mut self, mut game_state: GameState) {
+        if let Some(cooldown) = self.click_cooldown {
+            game_state.click_cooldown = cooldown;
+        }
+        if let Some(step) = self.max_timer_step {
+            game_state.max_timer_step = step;
+        }
+        if let Some(chance) = self.event_chance {
+            game_state.event_chance = chance;
+        }
+        game_state.event_duration = self.event_duration;
+        game_state.number_format = self.preferences.number_format;
+        game_state.precision = self.precision.unwrap_or(self.preferences.precision);
+        game_state.wrap_selection = self.wrap_selection;
+        // Saves know achievements by name, so carry completion over to the definitions in play
+        let mut achievements = self.achievements.clone();
+        for achievement in &mut achievements {
+            achievement.completed = game_state.achievements.iter().any(|a| a.completed && a.name == achievement.name);
+        }
+        game_state.achievements = achievements;
+        self.game_state = game_state;
+        self.list_state = ListState::default().with_selected(Some(0));
+        // A loaded save already past a milestone has nothing to celebrate
+        self.milestone_values = self.game_state.milestone_values();
+        self.snap_gold();
+    }
+
+    /// Applies settings kept in the preferences file wherever no flag overrides them, and
+    /// from then on writes changes made in game back to `path`.
+    fn use_preferences(&mut self, preferences: Preferences, path: Option<PathBuf>) {
+        if !self.theme_from_flag
+            && let Some(theme) = Theme::by_name(&preferences.theme)
+        {
+            self.theme = theme.with_color(self.theme.color);
+        }
+        self.accessible |= preferences.accessible;
+        self.game_state.number_format = preferences.number_format;
+        self.game_state.precision = self.precision.unwrap_or(preferences.precision);
+        self.preferences = preferences;
+        self.preferences_path = path;
+    }
+
+    // A failed write only costs the setting next launch, so it's a toast rather than an error
+    fn save_preferences(&mut self) {
+        if let Some(path) = &self.preferences_path
+            && let Err(err) = self.preferences.save(path)
+        {
+            self.show_toast(format!("Couldn't save preferences to {}: {}", path.display(), err));
+        }
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.game_state.filter = filter;
+        self.game_state.clamp_selection();
+        self.list_state.select(Some(self.game_state.selected_upgrade));
+    }
+
+    fn on_filter_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char(c) => {
+                let mut filter = self.game_state.filter.clone();
+                filter.push(c);
+                self.set_filter(filter);
+            }
+            KeyCode::Backspace => {
+                let mut filter = self.game_state.filter.clone();
+                filter.pop();
+                self.set_filter(filter);
+            }
+            KeyCode::Enter => self.editing_filter = false,
+            KeyCode::Esc => {
+                self.editing_filter = false;
+                self.set_filter(String::new());
+            }
+            _ => {}
+        }
+    }
+
+    fn reset_game(&mut self) {
+        // Starting over doesn't make anyone a new player
+        let tutorial_seen = self.game_state.tutorial_seen;
+        self.replace_game_state(new_game(self.difficulty, self.seed));
+        self.game_state.tutorial_seen = tutorial_seen;
+        if let Some(gold) = self.start_gold {
+            self.game_state.gold = gold;
+        }
+        // Resetting is an explicit choice to discard the old save, even an unreadable one
+        self.save_blocked = None;
+        match save::delete_save(&self.save_path) {
+            Ok(()) => self.show_toast("Progress reset".to_string()),
+            Err(err) => self.show_toast(format!("Reset, but failed to delete save: {}", err)),
+        }
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        self.last_save = Instant::now();
+        // Quitting from the profile menu: nothing was loaded, so there's nothing to save
+        if matches!(self.overlay, Some(Overlay::Profiles { .. })) {
+            return Ok(());
+        }
+        if let Some(reason) = &self.save_blocked {
+            return Err(io::Error::other(reason.clone()));
+        }
+        save::save_game(&self.game_state, &self.save_path)
+    }
+
+    fn show_toast(&mut self, message: String) {
+        self.toast = Some((message, Instant::now()));
+        self.dirty = true;
+    }
+
+    fn active_toast(&self) -> Option<&str> {
+        self.toast.as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < TOAST_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    fn select_next(&mut self) {
+        self.game_state.select_next();
+        self.list_state.select(Some(self.game_state.selected_upgrade));
+    }
+
+    fn select_previous(&mut self) {
+        self.game_state.select_previous();
+        self.list_state.select(Some(self.game_state.selected_upgrade));
+    }
+
+    // Rows that fit in the list as last drawn, so a page jump moves one screenful
+    fn page_rows(&self) -> usize {
+        let visible = self.list_area.height.saturating_sub(2);
+        match self.list_row_heights.first() {
+            Some(&row) if row > 0 && visible >= row => (visible / row) as usize,
+            _ => DEFAULT_PAGE_ROWS,
+        }
+    }
+
+    fn page_down(&mut self) {
+        self.game_state.select_down_by(self.page_rows());
+        self.list_state.select(Some(self.game_state.selected_upgrade));
+    }
+
+    fn page_up(&mut self) {
+        self.game_state.select_up_by(self.page_rows());
+        self.list_state.select(Some(self.game_state.selected_upgrade));
+    }
+
+    fn select_first_affordable(&mut self) {
+        if self.game_state.select_first_affordable() {
+            self.list_state.select(Some(self.game_state.selected_upgrade));
+        }
+    }
+
+    fn switch_tab(&mut self, tab: Tab) {
+        self.game_state.switch_tab(tab);
+        // Scrolling restarts at the top; the list scrolls down again to the restored row
+        self.list_state = ListState::default().with_selected(Some(self.game_state.selected_upgrade));
+    }
+
+    // Cheap summary of the values a tick can change on screen
+    fn display_key(&self) -> String {
+        let state = &self.game_state;
+        if self.show_precise {
+            // Every tick changes the unrounded gold, so just redraw
+            return format!("{}", state.gold);
+        }
+        // The last-click readout shows tenths for a while, then settles into whole seconds
+        let since_click = state.time_since_last_click().as_secs_f64();
+        let since_click = if since_click < 10.0 { (since_click * 10.0).floor() / 10.0 } else { since_click.floor() };
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}|{}",
+            since_click,
+            state.nugget.is_some(),
+            state.format(state.gold),
+            state.format(state.total_gold_earned),
+            state.format(state.gold_per_second),
+            state.active_events.len(),
+            state.combo,
+            state.achievements.iter().filter(|a| a.completed).count(),
+        )
+    }
+
+    fn on_tick(&mut self) {
+        if matches!(self.overlay, Some(Overlay::Welcome { .. } | Overlay::Profiles { .. })) {
+            return;
+        }
+        let before = self.display_key();
+        let unlocked = self.game_state.update();
+        if self.display_key() != before {
+            self.dirty = true;
+        }
+
+        self.ease_progress();
+        self.ease_gold();
+
+        if !self.floating_gains.is_empty() {
+            self.floating_gains.retain(|(_, at)| at.elapsed() < FLOATING_GAIN_DURATION);
+            self.dirty = true;
+        }
+
+        if !unlocked.is_empty() {
+            self.ring_bell();
+            // Newly finished achievements may have just dropped out of the list
+            self.game_state.clamp_selection();
+            self.list_state.select(Some(self.game_state.selected_upgrade));
+        }
+        for index in unlocked {
+            self.achievement_queue.push_back(self.game_state.achievements[index].name.clone());
+        }
+        let banner_done = self.achievement_banner.as_ref()
+            .is_none_or(|(_, shown_at)| shown_at.elapsed() >= ACHIEVEMENT_BANNER_DURATION);
+        if banner_done && (self.achievement_banner.is_some() || !self.achievement_queue.is_empty()) {
+            self.achievement_banner = self.achievement_queue.pop_front().map(|name| (name, Instant::now()));
+            self.dirty = true;
+        }
+
+        // Clicks and purchases since the last tick are caught here too
+        let before = std::mem::replace(&mut self.milestone_values, self.game_state.milestone_values());
+        for ((milestone, message), (before, after)) in CELEBRATIONS.iter().zip(before.into_iter().zip(self.milestone_values)) {
+            if before < milestone.threshold() && after >= milestone.threshold() {
+                self.celebration_queue.push_back(message);
+                self.ring_bell();
+            }
+        }
+        let celebration_done = self.celebration.is_none_or(|(_, shown_at)| shown_at.elapsed() >= CELEBRATION_DURATION);
+        if celebration_done && (self.celebration.is_some() || !self.celebration_queue.is_empty()) {
+            self.celebration = self.celebration_queue.pop_front().map(|message| (message, Instant::now()));
+            self.dirty = true;
+        }
+
+        if let Some(goal) = self.game_state.check_goal() {
+            self.show_toast(format!("Goal reached! {} gold", self.game_state.format(goal)));
+            self.ring_bell();
+        }
+
+        if self.last_save.elapsed() >= AUTOSAVE_INTERVAL
+            && let Err(err) = self.save()
+        {
+            self.show_toast(format!("Autosave failed: {}", err));
+        }
+    }
+
+    // Moves the gauge a share of the remaining distance that depends only on elapsed
+    // time, so it glides the same at any tick rate and settles within a few tenths of a second
+    fn ease_progress(&mut self) {
+        let elapsed = self.progress_updated_at.elapsed().as_secs_f64();
+        self.progress_updated_at = Instant::now();
+        let (target, _) = gold_progress(&self.game_state);
+        let gap = target - self.displayed_progress;
+        if gap.abs() < 0.001 {
+            self.displayed_progress = target;
+            return;
+        }
+        self.displayed_progress = (self.displayed_progress + gap * (1.0 - (-elapsed / PROGRESS_EASING).exp())).clamp(0.0, 1.0);
+        self.dirty = true;
+    }
+
+    // Counts the header gold up the same way. Anything that lowers gold shows at once, so the
+    // counter never shows gold that's already been spent
+    fn ease_gold(&mut self) {
+        let elapsed = self.gold_updated_at.elapsed().as_secs_f64();
+        self.gold_updated_at = Instant::now();
+        let target = self.game_state.gold;
+        let gap = target - self.displayed_gold;
+        if gap <= target * 1e-9 {
+            self.displayed_gold = target;
+            return;
+        }
+        self.displayed_gold += gap * (1.0 - (-elapsed / GOLD_EASING).exp());
+        self.dirty = true;
+    }
+
+    // Skips the count-up, for gold the player just grabbed themselves
+    fn snap_gold(&mut self) {
+        self.displayed_gold = self.game_state.gold;
+        self.gold_updated_at = Instant::now();
+    }
+
+    fn on_key(&mut self, key: KeyCode) {
+        self.dirty = true;
+        if self.overlay.is_some() {
+            self.on_overlay_key(key);
+            return;
+        }
+        if self.editing_filter {
+            self.on_filter_key(key);
+            return;
+        }
+
+        // Keys bound in keybinds.toml win over the cheats
+        if self.debug && self.keymap.action_for(key).is_none() && self.on_debug_key(key) {
+            return;
+        }
+        // The tutorial takes Enter and Esc; everything else still plays the game
+        if let Some(step) = self.tutorial_step {
+            match key {
+                KeyCode::Enter if step + 1 < TUTORIAL_STEPS.len() => self.tutorial_step = Some(step + 1),
+                KeyCode::Enter | KeyCode::Esc => {
+                    self.tutorial_step = None;
+                    self.game_state.tutorial_seen = true;
+                }
+                _ => {}
+            }
+            if matches!(key, KeyCode::Enter | KeyCode::Esc) {
+                return;
+            }
+        }
+
+        if self.focus == Focus::Tabs && matches!(key, KeyCode::Left | KeyCode::Right) {
+            let index = tab_index(&self.game_state.current_tab);
+            let index = if key == KeyCode::Left {
+                (index + TAB_TITLES.len() - 1) % TAB_TITLES.len()
+            } else {
+                (index + 1) % TAB_TITLES.len()
+            };
+            self.switch_tab(TABS[index]);
+            return;
+        }
+
+        let Some(action) = self.keymap.action_for(key) else {
+            return;
+        };
+        match action {
+            Action::FocusNext => self.focus = self.focus.next(),
+            Action::FocusPrevious => self.focus = self.focus.previous(),
+            // Enter acts on whichever pane has focus
+            Action::BuyUpgrade if self.focus == Focus::Mining => self.click(),
+            Action::BuyUpgrade if self.focus == Focus::Tabs => self.focus = Focus::List,
+            Action::Quit if self.confirm_quit => self.overlay = Some(Overlay::ConfirmQuit),
+            Action::Quit => self.should_quit = true,
+            Action::Click => self.click(),
+            Action::CollectNugget => self.collect_nugget(),
+            Action::Save => match self.save() {
+                Ok(()) => self.show_toast("Saved!".to_string()),
+                Err(err) => self.show_toast(format!("Save failed: {}", err)),
+            },
+            Action::BuyUpgrade => {
+                if self.game_state.buy_selected() {
+                    self.ring_bell();
+                }
+            }
+            Action::Sell => match self.game_state.sell_selected() {
+                Some(refund) => {
+                    self.snap_gold();
+                    self.show_toast(format!("Sold for {} gold", self.game_state.format(refund)));
+                }
+                None => self.show_toast("Nothing to sell here".to_string()),
+            },
+            Action::BuyAll => match self.game_state.buy_all_affordable() {
+                0 => self.show_toast("Nothing affordable".to_string()),
+                count => {
+                    self.ring_bell();
+                    self.show_toast(format!("Bought {} upgrade{}", count, if count == 1 { "" } else { "s" }));
+                }
+            },
+            Action::SelectUp => self.select_previous(),
+            Action::SelectDown => self.select_next(),
+            Action::PageUp => self.page_up(),
+            Action::PageDown => self.page_down(),
+            Action::SelectAffordable => self.select_first_affordable(),
+            Action::Recommend => match self.game_state.select_recommended() {
+                Some(name) => {
+                    self.list_state = ListState::default().with_selected(Some(self.game_state.selected_upgrade));
+                    self.focus = Focus::List;
+                    self.show_toast(format!("Recommended: {} \u{2014} best gold/sec per cost", name));
+                }
+                None => self.show_toast("Nothing to recommend yet: no upgrade would add gold/sec".to_string()),
+            },
+            Action::BuyToMilestone => match self.game_state.buy_to_milestone() {
+                0 => self.show_toast("Can't afford any copies toward the next milestone".to_string()),
+                count => {
+                    self.ring_bell();
+                    self.show_toast(format!("Bought {} toward the next milestone", count));
+                }
+            },
+            Action::ToggleAutoBuy if !prestige::auto_buy_unlocked(&self.game_state.prestige_upgrades) => {
+                self.show_toast("Buy Mine Managers in the prestige shop to unlock auto-buy".to_string());
+            }
+            Action::ToggleAutoBuy => match self.game_state.toggle_auto_buy() {
+                Some((name, true)) => self.show_toast(format!("Auto-buying {}", name)),
+                Some((name, false)) => self.show_toast(format!("Stopped auto-buying {}", name)),
+                None => {}
+            },
+            Action::Undo => match self.game_state.undo_purchase() {
+                Some(count) => self.show_toast(format!("Undid {} upgrade{}", count, if count == 1 { "" } else { "s" })),
+                None => self.show_toast("Nothing to undo".to_string()),
+            },
+            Action::Filter => {
+                if matches!(self.game_state.current_tab, Tab::Passive | Tab::Click) {
+                    self.editing_filter = true;
+                }
+            }
+            // Esc drops the filter first, then the goal
+            Action::ClearFilter if self.game_state.filter.is_empty() && self.game_state.goal.is_some() => {
+                self.game_state.goal = None;
+                self.show_toast("Goal cleared".to_string());
+            }
+            Action::ClearFilter => self.set_filter(String::new()),
+            Action::ShowDetails => {
+                if let Some(upgrade) = self.game_state.get_current_upgrades().get(self.game_state.selected_upgrade) {
+                    self.overlay = Some(Overlay::Details(upgrade.name.clone()));
+                }
+            }
+            Action::SetGoal => self.overlay = Some(Overlay::SetGoal { input: String::new(), error: None }),
+            Action::CycleNumberFormat => {
+                self.game_state.number_format = self.game_state.number_format.next();
+                self.preferences.number_format = self.game_state.number_format;
+                self.show_toast(format!("Number format: {}", self.game_state.number_format.label()));
+                self.save_preferences();
+            }
+            Action::CycleSort => {
+                self.game_state.sort_mode = self.game_state.sort_mode.next();
+                self.show_toast(format!("Sorting: {}", self.game_state.sort_mode.label()));
+            }
+            Action::Reset => self.overlay = Some(Overlay::ConfirmReset),
+            Action::Export => self.overlay = Some(Overlay::Export(save::encode_save(&self.game_state))),
+            Action::Import => self.overlay = Some(Overlay::Import { input: String::new(), error: None }),
+            Action::Share => self.overlay = Some(Overlay::Share(self.game_state.share_summary())),
+            Action::CycleTheme => {
+                self.theme = self.theme.next();
+                self.preferences.theme = self.theme.name.to_string();
+                self.show_toast(format!("Theme: {}", self.theme.name));
+                self.save_preferences();
+            }
+            Action::Pause => self.game_state.paused = !self.game_state.paused,
+            Action::ToggleHelp => self.game_state.show_help = !self.game_state.show_help,
+            Action::ToggleCompleted => {
+                self.game_state.hide_completed = !self.game_state.hide_completed;
+                self.game_state.clamp_selection();
+                self.list_state.select(Some(self.game_state.selected_upgrade));
+                self.show_toast(if self.game_state.hide_completed { "Hiding completed achievements" } else { "Showing all achievements" }.to_string());
+            }
+            Action::TogglePrecise => self.show_precise = !self.show_precise,
+            Action::News => self.overlay = Some(Overlay::Document { document: Document::News, scroll: 0 }),
+            Action::PrestigeHistory => self.overlay = Some(Overlay::Document { document: Document::PrestigeHistory, scroll: 0 }),
+            Action::TabPassive => self.switch_tab(Tab::Passive),
+            Action::TabClick => self.switch_tab(Tab::Click),
+            Action::TabAchievements => self.switch_tab(Tab::Achievements),
+            Action::TabStats => self.switch_tab(Tab::Stats),
+            Action::TabPrestige => self.switch_tab(Tab::Prestige),
+            Action::Prestige => match self.game_state.prestige_points_available() {
+                0 => self.show_toast("Earn 1M gold this run to unlock prestige".to_string()),
+                _ => self.overlay = Some(Overlay::ConfirmPrestige),
+            },
+        }
+    }
+
+    fn click(&mut self) {
+        if let Some(outcome) = self.game_state.click_for_gold() {
+            if self.floating_gains.len() == FLOATING_GAIN_CAPACITY {
+                self.floating_gains.pop_front();
+            }
+            self.floating_gains.push_back((outcome, Instant::now()));
+            self.snap_gold();
+        }
+    }
+
+    fn collect_nugget(&mut self) {
+        if let Some(reward) = self.game_state.collect_nugget() {
+            self.nugget_area = Rect::default();
+            self.snap_gold();
+            self.show_toast(format!("Golden nugget! +{} gold", self.game_state.format(reward)));
+        }
+    }
+
+    fn ring_bell(&mut self) {
+        self.bell_pending |= self.bell;
+    }
+
+    // Returns whether `key` was a cheat
+    fn on_debug_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('g') => {
+                self.game_state.earn(1e6);
+                self.show_toast("Debug: +1M gold".to_string());
+            }
+            KeyCode::Char('G') => {
+                self.game_state.gold = (self.game_state.gold * 10.0).min(GOLD_CAP);
+                self.show_toast("Debug: gold x10".to_string());
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    // Hit areas from the last frame point at the old layout until the next draw
+    fn on_resize(&mut self) {
+        self.mining_area = Rect::default();
+        self.nugget_area = Rect::default();
+        self.list_area = Rect::default();
+        self.list_row_heights.clear();
+        self.dirty = true;
+    }
+
+    fn on_mouse(&mut self, mouse: MouseEvent) {
+        if self.overlay.is_some() || mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+
+        let position = Position::new(mouse.column, mouse.row);
+        if self.nugget_area.contains(position) {
+            self.collect_nugget();
+        } else if self.mining_area.contains(position) {
+            // Goes through the same cooldown as the keyboard
+            self.focus = Focus::Mining;
+            self.click();
+            self.dirty = true;
+        } else if let Some(index) = self.list_row_at(position) {
+            self.focus = Focus::List;
+            // First click selects a row, clicking the selected row buys it
+            if index == self.game_state.selected_upgrade {
+                if self.game_state.buy_selected() {
+                    self.ring_bell();
+                }
+            } else {
+                self.game_state.selected_upgrade = index;
+                self.list_state.select(Some(index));
+            }
+            self.dirty = true;
+        }
+    }
+
+    fn list_row_at(&self, position: Position) -> Option<usize> {
+        let inner = self.list_area.inner(Margin::new(1, 1));
+        if !inner.contains(position) {
+            return None;
+        }
+
+        let mut row_top = inner.y;
+        for (index, height) in self.list_row_heights.iter().enumerate().skip(self.list_state.offset()) {
+            if position.y < row_top + height {
+                return Some(index);
+            }
+            row_top += height;
+        }
+        None
+    }
+
+    fn on_overlay_key(&mut self, key: KeyCode) {
+        let is_quit_key = self.keymap.action_for(key) == Some(Action::Quit);
+        let Some(overlay) = &mut self.overlay else {
+            return;
+        };
+
+        match (overlay, key) {
+            (Overlay::ConfirmQuit, KeyCode::Char('y')) => self.should_quit = true,
+            (Overlay::ConfirmQuit, _) if is_quit_key => self.should_quit = true,
+            (Overlay::ConfirmQuit, KeyCode::Char('n') | KeyCode::Esc) => self.overlay = None,
+            (Overlay::Profiles { .. }, _) if is_quit_key => self.should_quit = true,
+            (Overlay::Profiles { selected, .. }, KeyCode::Up) => *selected = selected.saturating_sub(1),
+            (Overlay::Profiles { names, selected }, KeyCode::Down) => *selected = (*selected + 1).min(names.len() - 1),
+            (Overlay::Profiles { names, selected }, KeyCode::Enter) => {
+                let path = match *selected {
+                    0 => self.save_path.clone(),
+                    index => save::profile_save_path(&names[index]),
+                };
+                self.overlay = None;
+                self.open_save(path);
+            }
+            (Overlay::Welcome { .. }, _) => {
+                self.overlay = None;
+                self.game_state.resume_clock();
+            }
+            (Overlay::ConfirmReset, KeyCode::Char('y')) => {
+                self.overlay = None;
+                self.reset_game();
+            }
+            (Overlay::ConfirmReset, KeyCode::Char('n') | KeyCode::Esc) => self.overlay = None,
+            (Overlay::ConfirmPrestige, KeyCode::Char('y')) => {
+                self.overlay = None;
+                let points = self.game_state.prestige();
+                self.list_state.select(Some(0));
+                self.show_toast(format!("Prestiged for {} point{}", points, if points == 1 { "" } else { "s" }));
+            }
+            (Overlay::ConfirmPrestige, KeyCode::Char('n') | KeyCode::Esc) => self.overlay = None,
+            (Overlay::Export(_) | Overlay::Share(_), KeyCode::Esc | KeyCode::Enter) => self.overlay = None,
+            (Overlay::Details(_), KeyCode::Esc) => self.overlay = None,
+            (Overlay::Details(_), _) if self.keymap.action_for(key) == Some(Action::ShowDetails) => self.overlay = None,
+            (Overlay::Document { scroll, .. }, KeyCode::Up) => *scroll = scroll.saturating_sub(1),
+            // ui() pulls this back in once it's past the end for the current terminal size
+            (Overlay::Document { scroll, .. }, KeyCode::Down) => *scroll = scroll.saturating_add(1),
+            (Overlay::Document { .. }, KeyCode::Esc) => self.overlay = None,
+            (Overlay::Import { input, error }, KeyCode::Char(c)) => {
+                input.push(c);
+                *error = None;
+            }
+            (Overlay::Import { input, error }, KeyCode::Backspace) => {
+                input.pop();
+                *error = None;
+            }
+            (Overlay::Import { input, error }, KeyCode::Enter) => match save::decode_save(input) {
+                Ok(game_state) => {
+                    self.replace_game_state(game_state);
+                    self.overlay = None;
+                    self.show_toast("Save imported".to_string());
+                }
+                Err(err) => *error = Some(err.to_string()),
+            },
+            (Overlay::Import { .. }, KeyCode::Esc) => self.overlay = None,
+            (Overlay::SetGoal { input, error }, KeyCode::Char(c)) => {
+                input.push(c);
+                *error = None;
+            }
+            (Overlay::SetGoal { input, error }, KeyCode::Backspace) => {
+                input.pop();
+                *error = None;
+            }
+            (Overlay::SetGoal { input, error }, KeyCode::Enter) => match GameState::parse_amount(input) {
+                Some(goal) if goal <= self.game_state.gold => {
+                    *error = Some(format!("You already have {} gold", self.game_state.format(goal)));
+                }
+                Some(goal) => {
+                    self.game_state.goal = Some(goal);
+                    self.overlay = None;
+                    self.show_toast(format!("Goal set: {} gold", self.game_state.format(goal)));
+                }
+                None => *error = Some("Enter an amount like 5000, 2.5M or 1e9".to_string()),
+            },
+            (Overlay::SetGoal { .. }, KeyCode::Esc) => self.overlay = None,
+            _ => {}
+        }
+    }
+}
+
+fn ui(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    if area.width < MIN_TERMINAL_WIDTH || area.height < MIN_TERMINAL_HEIGHT {
+        // Nothing clickable is on screen, so stale hit areas mustn't catch mouse clicks
+        app.mining_area = Rect::default();
+        app.nugget_area = Rect::default();
+        app.list_area = Rect::default();
+        let message = format!("Terminal too small (need at least {}x{})", MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT);
+        let text = Paragraph::new(message)
+            .style(app.theme.fg(app.theme.unaffordable))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        f.render_widget(text, centered_rect(area.width, 2, area));
+        return;
+    }
+
+    if app.compact || f.area().height < COMPACT_LAYOUT_HEIGHT {
+        render_compact_layout(f, app);
+    } else {
+        render_full_layout(f, app);
+    }
+
+    if app.show_precise {
+        render_precise_values(f, app);
+    }
+    if let Some(perf) = &app.perf {
+        render_perf(f, app, perf);
+    }
+
+    let theme = app.theme;
+    if let Some((name, shown_at)) = &app.achievement_banner {
+        let remaining = ACHIEVEMENT_BANNER_DURATION.saturating_sub(shown_at.elapsed()).as_secs_f64().ceil();
+        let area = centered_rect(50, 3, f.area());
+        let banner = Paragraph::new(Line::from(vec![
+            Span::styled(format!("Achievement Unlocked: {}!", name), theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+            Span::styled(format!(" ({:.0}s)", remaining), theme.fg(theme.muted)),
+        ]))
+        .block(bordered_block(&theme, "Achievement"))
+        .alignment(Alignment::Center);
+        f.render_widget(Clear, area);
+        f.render_widget(banner, area);
+    }
+    if let Some((message, _)) = app.celebration {
+        // Across the top of the screen, over the header
+        let area = Rect { height: 3.min(f.area().height), ..f.area() };
+        // Reversed, so even without colors it stands out as a solid bar
+        let banner = Paragraph::new(format!("\u{2605} {} \u{2605}", message))
+            .block(bordered_block(&theme, "Milestone"))
+            .style(theme.fg(theme.total).add_modifier(Modifier::BOLD | Modifier::REVERSED))
+            .alignment(Alignment::Center);
+        f.render_widget(Clear, area);
+        f.render_widget(banner, area);
+    }
+
+    if let Some(step) = app.tutorial_step {
+        render_tutorial(f, app, step);
+    }
+    if let Some(Overlay::Document { document, .. }) = &app.overlay {
+        let (_, lines) = document_text(app, *document);
+        let max_scroll = max_document_scroll(area, &lines);
+        if let Some(Overlay::Document { scroll, .. }) = &mut app.overlay {
+            *scroll = (*scroll).min(max_scroll);
+        }
+    }
+    if let Some(overlay) = &app.overlay {
+        render_overlay(f, app, overlay);
+    }
+}
+
+fn render_full_layout(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let paused = Span::styled(if app.game_state.paused { "  [PAUSED]" } else { "" }, theme.fg(theme.unaffordable).add_modifier(Modifier::BOLD));
+    let mut stats = vec![
+        Span::raw("Gold: "),
+        Span::styled(header_gold(app), theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+        Span::raw(" | Rate: "),
+    ];
+    stats.extend(rate_spans(app));
+    stats.extend([
+        Span::raw(" | Click: +"),
+        Span::styled(app.game_state.format(app.game_state.click_power), theme.fg(theme.click)),
+        Span::raw(" | Total: "),
+        Span::styled(app.game_state.format(app.game_state.total_gold_earned), theme.fg(theme.total)),
+    ]);
+
+    // Header; the art only replaces the title when there's room to spare for it
+    let area = f.area();
+    let show_banner = app.banner
+        && area.height >= BANNER_MIN_HEIGHT
+        && area.width as usize >= BANNER[0].len() + 4;
+    let mut header_lines = if show_banner {
+        let mut lines: Vec<Line> = BANNER.iter()
+            .map(|row| Line::from(Span::styled(*row, theme.fg(theme.gold).add_modifier(Modifier::BOLD))))
+            .collect();
+        lines.push(Line::from([stats, vec![paused]].concat()));
+        lines
+    } else {
+        vec![
+            Line::from(vec![
+                Span::styled("TERMINAL GOLD MINE", theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+                paused,
+            ]),
+            Line::from(stats),
+        ]
+    };
+    for event in &app.game_state.active_events {
+        let color = if event.kind.is_debuff() { theme.unaffordable } else { theme.event };
+        header_lines.push(Line::from(vec![
+            Span::styled(event.describe(), theme.fg(color).add_modifier(Modifier::BOLD))
+        ]));
+    }
+    if let Some(goal) = app.game_state.goal {
+        let eta = match app.game_state.goal_eta() {
+            Some(eta) => format!("ETA {}", format_duration(eta)),
+            None => "no income yet".to_string(),
+        };
+        header_lines.push(Line::from(vec![
+            Span::raw(format!("Goal {}: ", app.game_state.format(goal))),
+            Span::styled(progress_bar((app.game_state.gold / goal).min(1.0), GOAL_BAR_WIDTH), theme.fg(theme.gold)),
+            Span::styled(format!(" | {} (Esc clears)", eta), theme.fg(theme.muted)),
+        ]));
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(header_lines.len() as u16 + 2),
+            Constraint::Min(10),
+            Constraint::Length(3),
+        ].as_ref())
+        .split(area);
+
+    let header = Paragraph::new(header_lines)
+    .block(bordered_block(&theme, "Status"))
+    .alignment(Alignment::Center);
+    f.render_widget(header, chunks[0]);
+
+    // Main content: a fixed tab strip, with everything else below it
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+        .split(chunks[1]);
+
+    let tabs = Tabs::new(TAB_TITLES.to_vec())
+        .block(pane_block(app, Focus::Tabs, "Tabs"))
+        .style(theme.fg(theme.muted))
+        .highlight_style(theme.colors(Some(theme.text), Some(theme.tab)).add_modifier(Modifier::BOLD))
+        .select(tab_index(&app.game_state.current_tab));
+    f.render_widget(tabs, main_chunks[0]);
+
+    // Split the content area - mining on the left, tab content on the right,
+    // or stacked with mining on top when there isn't room for two columns
+    let (direction, constraints) = if f.area().width < NARROW_LAYOUT_WIDTH {
+        (Direction::Vertical, [Constraint::Percentage(40), Constraint::Percentage(60)])
+    } else {
+        (Direction::Horizontal, [Constraint::Percentage(50), Constraint::Percentage(50)])
+    };
+    let content_chunks = Layout::default()
+        .direction(direction)
+        .constraints(constraints.as_ref())
+        .split(main_chunks[1]);
+
+    app.mining_area = render_mining_column(f, app, content_chunks[0]);
+    app.nugget_area = render_nugget(f, app);
+    render_floating_gains(f, app);
+    render_tab_content(f, app, content_chunks[1]);
+
+    // Footer
+    let footer = Paragraph::new(footer_text(app))
+        .block(bordered_block(&theme, "Controls"))
+        .alignment(Alignment::Center);
+    f.render_widget(footer, chunks[2]);
+}
+
+const TAB_TITLES: [&str; 5] = ["1-Passive Upgrades", "2-Click Upgrades", "3-Achievements", "4-Stats", "5-Prestige"];
+// In tab_index order
+const TABS: [Tab; 5] = [Tab::Passive, Tab::Click, Tab::Achievements, Tab::Stats, Tab::Prestige];
+
+fn tab_index(tab: &Tab) -> usize {
+    match tab {
+        Tab::Passive => 0,
+        Tab::Click => 1,
+        Tab::Achievements => 2,
+        Tab::Stats => 3,
+        Tab::Prestige => 4,
+    }
+}
+
+// Production multiplier at the upgrade's next milestone: a whole "4" while it's small,
+// then the usual suffixes up to "MAX"
+fn next_milestone_multiplier(state: &GameState, upgrade: &Upgrade) -> String {
+    let multiplier = (upgrade.milestone_multiplier() * 2.0).min(GOLD_CAP);
+    if multiplier < 1_000.0 {
+        multiplier.to_string()
+    } else {
+        state.format(multiplier)
+    }
+}
+
+// "own 5 Shovel" / "earn 10.00K gold this run"
+fn unlock_requirement(state: &GameState, condition: &UnlockCondition) -> String {
+    match condition {
+        UnlockCondition::Owned(name, count) => format!("own {} {}", count, name),
+        UnlockCondition::TotalGold(gold) => format!("earn {} gold this run", state.format(*gold)),
+    }
+}
+
+// "5.00/sec", or while a debuff holds production down, "5.00/sec (↓ from 10.00)" in red with
+// the normal rate struck through
+fn rate_spans(app: &App) -> Vec<Span<'static>> {
+    let theme = app.theme;
+    let state = &app.game_state;
+    let rate = format!("{}/sec", state.format(state.gold_per_second));
+    if state.gold_per_second >= state.base_gold_per_second {
+        return vec![Span::styled(rate, theme.fg(theme.rate))];
+    }
+    vec![
+        Span::styled(rate, theme.fg(theme.unaffordable).add_modifier(Modifier::BOLD)),
+        Span::styled(" (\u{2193} from ", theme.fg(theme.unaffordable)),
+        Span::styled(state.format(state.base_gold_per_second), theme.fg(theme.muted).add_modifier(Modifier::CROSSED_OUT)),
+        Span::styled(")", theme.fg(theme.unaffordable)),
+    ]
+}
+
+// Gold for the status line. Past --whole-gold-above it's rounded down to whole gold, so the
+// decimals stop churning every tick; suffixed and scientific values keep --precision, since
+// there the decimals are the only digits that move
+fn header_gold(app: &App) -> String {
+    let state = &app.game_state;
+    let gold = app.displayed_gold;
+    let whole = app.whole_gold_above.is_some_and(|threshold| gold >= threshold)
+        && match state.number_format {
+            NumberFormat::Short => gold < 1_000.0,
+            NumberFormat::Grouped => gold < GROUPED_DIGITS_LIMIT,
+            NumberFormat::Scientific => false,
+        };
+    if whole {
+        GameState::format_number(gold.floor(), state.number_format, 0)
+    } else {
+        state.format(gold)
+    }
+}
+
+// Prefix for prices so affordability doesn't rest on red vs green alone; shown with
+// --accessible, and always when there are no colors to tell apart
+fn affordability_mark(app: &App, affordable: bool) -> &'static str {
+    match (app.accessible || !app.theme.color, affordable) {
+        (false, _) => "",
+        (true, true) => "\u{2713} ",
+        (true, false) => "\u{2717} ",
+    }
+}
+
+fn footer_text(app: &App) -> &str {
+    if let Some(toast) = app.active_toast() {
+        toast
+    } else if app.game_state.show_help {
+        "SPACE: Mine gold | Tab/Shift+Tab: Focus pane | Up/Down: Select | PgUp/PgDn: Page | A: First affordable | Shift+R: Recommend | ENTER: Buy | Shift+S: Sell | W: Details | B: Buy all | M: Buy to milestone | Z: Auto-buy | U: Undo buy | N: Golden nugget | Y: Set goal | 1: Passive | 2: Click | 3: Achievements | 4: Stats | 5: Prestige | D: Hide done | /: Filter | O: Sort | F: Number format | S: Save | E/I: Export/Import | Shift+E: Share score | T: Theme | R: Reset | P: Pause | Shift+P: Prestige | H: Toggle help | V: Precise values | L: Prestige history | C: What's new | Q: Quit"
+    } else {
+        "Press H for help | 1-5: Switch tabs | S to save | Q to quit"
+    }
+}
+
+// Single pane for small terminals: one status line, the current tab and a one-line
+// footer. There's no mining panel, so clicking is keyboard-only here.
+fn render_compact_layout(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)].as_ref())
+        .split(f.area());
+
+    let mut status = vec![
+        Span::styled(header_gold(app), theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+        Span::raw(" gold | "),
+        Span::styled(format!("{}/sec", app.game_state.format(app.game_state.gold_per_second)), theme.fg(theme.rate)),
+    ];
+    if app.game_state.gold_per_second < app.game_state.base_gold_per_second {
+        status.push(Span::styled(" \u{2193}", theme.fg(theme.unaffordable).add_modifier(Modifier::BOLD)));
+    }
+    status.extend([
+        Span::raw(" | "),
+        Span::styled(format!("+{}/click", app.game_state.format(app.game_state.click_power)), theme.fg(theme.click)),
+        Span::raw(" | "),
+        Span::styled(TAB_TITLES[tab_index(&app.game_state.current_tab)], theme.fg(theme.heading)),
+    ]);
+    for event in &app.game_state.active_events {
+        let color = if event.kind.is_debuff() { theme.unaffordable } else { theme.event };
+        status.push(Span::styled(format!(" | {}", event.kind.name()), theme.fg(color).add_modifier(Modifier::BOLD)));
+    }
+    if app.game_state.nugget.is_some() {
+        status.push(Span::styled(" | Nugget! (N)", theme.fg(theme.gold).add_modifier(Modifier::BOLD)));
+    }
+    if let Some(goal) = app.game_state.goal {
+        let percent = (app.game_state.gold / goal * 100.0).clamp(0.0, 100.0).floor();
+        status.push(Span::styled(format!(" | Goal {:.0}%", percent), theme.fg(theme.gold)));
+    }
+    if app.game_state.paused {
+        status.push(Span::styled(" [PAUSED]", theme.fg(theme.unaffordable).add_modifier(Modifier::BOLD)));
+    }
+    f.render_widget(Paragraph::new(Line::from(status)), chunks[0]);
+
+    app.mining_area = Rect::default();
+    app.nugget_area = Rect::default();
+    render_tab_content(f, app, chunks[1]);
+
+    f.render_widget(Paragraph::new(footer_text(app)).style(theme.fg(theme.muted)), chunks[2]);
+}
+
+// Selected tab's list or panels; the normal and compact layouts both use this
+fn render_tab_content(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    app.list_area = Rect::default();
+    app.list_row_heights.clear();
+
+    // Content based on selected tab
+    match app.game_state.current_tab {
+        Tab::Passive | Tab::Click => {
+            // Right side - Upgrades list
+            let current_upgrades = app.game_state.get_current_upgrades();
+            let upgrade_items: Vec<ListItem> = current_upgrades
+                .iter()
+                .map(|upgrade| {
+                    let unlocked = app.game_state.is_unlocked(upgrade);
+                    let affordable = upgrade.can_afford(app.game_state.gold);
+                    let cost_color = if affordable { theme.affordable } else { theme.unaffordable };
+
+                    let effect_text = match upgrade.upgrade_type {
+                        UpgradeType::Passive => format!("+{}/sec", app.game_state.format(upgrade.unit_production())),
+                        UpgradeType::Click => format!("+{}/click", app.game_state.format(upgrade.unit_production())),
+                        UpgradeType::AutoClick => format!("+{} clicks/sec", app.game_state.format(upgrade.unit_production())),
+                    };
+
+                    // Click upgrades feed manual clicks, which aren't attributed to them
+                    let lifetime_text = match upgrade.upgrade_type {
+                        _ if upgrade.max_owned == 0 => String::new(),
+                        UpgradeType::Click => String::new(),
+                        UpgradeType::Passive | UpgradeType::AutoClick => {
+                            format!(" | Made: {} (most owned: {})", app.game_state.format(upgrade.lifetime_gold), upgrade.max_owned)
+                        }
+                    };
+
+                    let next_multiplier = next_milestone_multiplier(&app.game_state, upgrade);
+                    let name_color = if unlocked { theme.heading } else { theme.muted };
+                    let mut header = Vec::new();
+                    if upgrade.auto_buy {
+                        header.push(Span::styled("[A] ", theme.fg(theme.event).add_modifier(Modifier::BOLD)));
+                    }
+                    header.extend([
+                        Span::styled(format!("{} ({})", upgrade.name, upgrade.owned), theme.fg(name_color).add_modifier(Modifier::BOLD)),
+                        Span::styled(
+                            format!(
+                                "  {}/{} to {}x ({} for {} more)",
+                                upgrade.owned,
+                                upgrade.next_milestone(),
+                                next_multiplier,
+                                app.game_state.format(upgrade.bulk_cost(upgrade.copies_to_milestone())),
+                                upgrade.copies_to_milestone(),
+                            ),
+                            theme.fg(theme.muted),
+                        ),
+                    ]);
+                    if upgrade.synergy_bonus > 0.0 {
+                        let sources: Vec<&str> = SYNERGIES.iter()
+                            .filter(|s| s.target == upgrade.name)
+                            .map(|s| s.source)
+                            .collect();
+                        header.push(Span::styled(
+                            format!("  +{:.0}% from {}", upgrade.synergy_bonus * 100.0, sources.join(", ")),
+                            theme.fg(theme.event),
+                        ));
+                    }
+
+                    let price_line = match &upgrade.unlock_condition {
+                        Some(condition) if !unlocked => {
+                            Line::from(Span::styled(
+                                format!("Locked: {} | Cost: {}", unlock_requirement(&app.game_state, condition), app.game_state.format(upgrade.current_cost())),
+                                theme.fg(theme.muted),
+                            ))
+                        }
+                        _ => Line::from(vec![
+                            Span::raw("Cost: "),
+                            Span::styled(
+                                format!("{}{}", affordability_mark(app, affordable), app.game_state.format(upgrade.current_cost())),
+                                theme.fg(cost_color),
+                            ),
+                            Span::raw(" | "),
+                            Span::styled(effect_text, theme.fg(theme.rate)),
+                            Span::styled(lifetime_text, theme.fg(theme.total)),
+                        ]),
+                    };
+                    let content = vec![
+                        Line::from(header),
+                        price_line,
+                        Line::from(vec![
+                            Span::styled(upgrade.description.clone(), theme.fg(theme.muted))
+                        ]),
+                    ];
+
+                    ListItem::new(content)
+                })
+                .collect();
+
+            let mut title = if app.editing_filter || !app.game_state.filter.is_empty() {
+                let cursor = if app.editing_filter { "_" } else { "" };
+                format!("Gold: {} (Filter: {}{}, Esc clears)", app.game_state.format(app.game_state.gold), app.game_state.filter, cursor)
+            } else {
+                format!("Gold: {} (Up/Down select, Enter buy)", app.game_state.format(app.game_state.gold))
+            };
+            if app.game_state.sort_mode != SortMode::Default {
+                title.push_str(&format!(" [{}]", app.game_state.sort_mode.label()));
+            }
+            app.list_area = area;
+            app.list_row_heights = upgrade_items.iter().map(|item| item.height() as u16).collect();
+            let upgrades = List::new(upgrade_items)
+                .block(pane_block(app, Focus::List, title))
+                .highlight_style(theme.bg(theme.highlight).add_modifier(Modifier::BOLD))
+                .highlight_symbol("> ");
+            f.render_stateful_widget(upgrades, area, &mut app.list_state);
+        }
+
+        Tab::Achievements => {
+            // Right side - Achievements list
+            let achievement_items: Vec<ListItem> = app.game_state.visible_achievements()
+                .into_iter()
+                .map(|achievement| {
+                    let status_color = if achievement.completed {
+                        theme.affordable
+                    } else {
+                        theme.gold
+                    };
+
+                    let status_symbol = if achievement.completed { "[DONE]" } else { "[    ]" };
+                    let fraction = if achievement.completed {
+                        1.0
+                    } else {
+                        (app.game_state.achievement_value(&achievement.achievement_type) / achievement.target).clamp(0.0, 1.0)
+                    };
+
+                    let current_value = match achievement.achievement_type {
+                        AchievementType::TotalGold(_) => app.game_state.format(app.game_state.total_gold_earned),
+                        AchievementType::GoldPerSecond(_) => app.game_state.format(app.game_state.gold_per_second),
+                        AchievementType::TotalClicks(_) => app.game_state.total_clicks.to_string(),
+                        AchievementType::ClickPower(_) => app.game_state.format(app.game_state.click_power),
+                        AchievementType::UpgradesPurchased(_) => app.game_state.total_upgrades_purchased.to_string(),
+                    };
+
+                    let content = vec![
+                        Line::from(vec![
+                            Span::styled(format!("{} {}", status_symbol, achievement.name), theme.fg(status_color).add_modifier(Modifier::BOLD))
+                        ]),
+                        Line::from(vec![
+                            Span::styled(achievement.description.clone(), theme.fg(theme.muted))
+                        ]),
+                        Line::from(vec![
+                            Span::raw("Progress: "),
+                            Span::styled(current_value, theme.fg(theme.click)),
+                            Span::raw(" / "),
+                            Span::styled(app.game_state.format(achievement.target), theme.fg(theme.text)),
+                        ]),
+                        Line::from(Span::styled(progress_bar(fraction, ACHIEVEMENT_BAR_WIDTH), theme.fg(status_color))),
+                        Line::from(vec![
+                            Span::raw("Reward: "),
+                            Span::styled(format!("+{:.0}% production", (achievement.reward - 1.0) * 100.0), theme.fg(theme.rate)),
+                        ]),
+                    ];
+
+                    ListItem::new(content)
+                })
+                .collect();
+
+            let completed_count = app.game_state.achievements.iter().filter(|a| a.completed).count();
+            let total_count = app.game_state.achievements.len();
+
+            app.list_area = area;
+            app.list_row_heights = achievement_items.iter().map(|item| item.height() as u16).collect();
+            let achievements = List::new(achievement_items)
+                .block(pane_block(app, Focus::List, format!(
+                    "Long-term Goals ({}/{}){}",
+                    completed_count,
+                    total_count,
+                    if app.game_state.hide_completed { " [hiding done, D shows]" } else { "" },
+                )))
+                .highlight_style(theme.bg(theme.highlight).add_modifier(Modifier::BOLD))
+                .highlight_symbol("> ");
+            f.render_stateful_widget(achievements, area, &mut app.list_state);
+        }
+
+        Tab::Stats => {
+            let stats_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(8), Constraint::Length(14), Constraint::Min(3)].as_ref())
+                .split(area);
+
+            // Sparkline only takes integers and overflows on huge ones, so plot each rate
+            // as a fraction of the peak
+            let peak = app.game_state.rate_history.iter().copied().fold(0.0, f64::max);
+            let history: Vec<u64> = app.game_state.rate_history.iter()
+                .map(|rate| if peak > 0.0 { (rate / peak * 10_000.0) as u64 } else { 0 })
+                .collect();
+            let graph = Sparkline::default()
+                .block(bordered_block(&theme, format!("Gold/sec, last {}s (peak {})", RATE_HISTORY_CAPACITY, app.game_state.format(peak))))
+                .data(&history)
+                .style(theme.fg(theme.rate));
+            f.render_widget(graph, stats_chunks[0]);
+
+            let completed_count = app.game_state.achievements.iter().filter(|a| a.completed).count();
+            // What passive income would be with no achievements completed
+            let rate_without_achievements = app.game_state.passive_rate(1.0);
+            let stat_line = |label: &str, value: String| Line::from(vec![
+                Span::raw(format!("{}: ", label)),
+                Span::styled(value, theme.fg(theme.click)),
+            ]);
+            let stats = Paragraph::new(vec![
+                stat_line("Total gold earned", app.game_state.format(app.game_state.total_gold_earned)),
+                stat_line("Difficulty", app.game_state.difficulty.label().to_string()),
+                stat_line("Seed", app.game_state.seed.to_string()),
+                stat_line("Total gold spent", app.game_state.format(app.game_state.total_gold_spent)),
+                stat_line("Net worth", app.game_state.format(app.game_state.net_worth())),
+                stat_line("Passive income", format!("{}/sec from upgrades", app.game_state.format(app.game_state.gold_per_second))),
+                stat_line("Active income", format!(
+                    "{}/sec from clicking (last {}s)",
+                    app.game_state.format(app.game_state.active_income()),
+                    CLICK_RATE_WINDOW.as_secs(),
+                )),
+                stat_line("Gold per click", app.game_state.format(app.game_state.click_power)),
+                stat_line("Total clicks", app.game_state.total_clicks.to_string()),
+                stat_line("Upgrades purchased", app.game_state.total_upgrades_purchased.to_string()),
+                stat_line("Achievements", format!("{}/{}", completed_count, app.game_state.achievements.len())),
+                stat_line("Achievement bonus", format!(
+                    "x{:.2}, +{}/sec ({}/sec without)",
+                    app.game_state.achievement_multiplier,
+                    app.game_state.format(app.game_state.gold_per_second - rate_without_achievements),
+                    app.game_state.format(rate_without_achievements),
+                )),
+            ])
+            .block(pane_block(app, Focus::List, "Statistics"));
+            f.render_widget(stats, stats_chunks[1]);
+
+            let breakdown = app.game_state.passive_breakdown();
+            let breakdown_lines: Vec<Line> = if breakdown.is_empty() {
+                vec![Line::from(Span::styled("Buy a passive upgrade to start earning gold/sec", theme.fg(theme.muted)))]
+            } else {
+                breakdown.iter()
+                    .map(|(upgrade, rate, share)| Line::from(vec![
+                        Span::raw(format!("{} x{}: ", upgrade.name, upgrade.owned)),
+                        Span::styled(format!("{}/sec", app.game_state.format(*rate)), theme.fg(theme.rate)),
+                        Span::styled(format!(" ({:.1}%)", share * 100.0), theme.fg(theme.muted)),
+                    ]))
+                    .collect()
+            };
+            let breakdown_panel = Paragraph::new(breakdown_lines)
+                .block(bordered_block(&theme, "Income by upgrade"));
+            f.render_widget(breakdown_panel, stats_chunks[2]);
+        }
+        Tab::Prestige => {
+            let prestige_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(6), Constraint::Min(3)].as_ref())
+                .split(area);
+
+            let state = &app.game_state;
+            let available = state.prestige_points_available();
+            let summary = Paragraph::new(vec![
+                Line::from(vec![
+                    Span::raw("Prestige points: "),
+                    Span::styled(state.prestige_points.to_string(), theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" (+{:.0}% production)", (state.prestige_multiplier() - 1.0) * 100.0), theme.fg(theme.rate)),
+                ]),
+                Line::from(vec![
+                    Span::raw("Prestiging now earns "),
+                    Span::styled(format!("+{} points", available), theme.fg(if available > 0 { theme.affordable } else { theme.unaffordable })),
+                ]),
+                Line::from(""),
+                Line::from(Span::styled("Press Shift+P to prestige: resets gold and upgrades, keeps achievements and this shop. L shows past runs", theme.fg(theme.muted))),
+            ])
+            .block(bordered_block(&theme, format!("Prestige ({} so far)", state.prestige_count)))
+            .wrap(Wrap { trim: true });
+            f.render_widget(summary, prestige_chunks[0]);
+
+            let shop_items: Vec<ListItem> = state.prestige_upgrades.iter()
+                .map(|upgrade| {
+                    let level = match upgrade.max_level {
+                        Some(max) => format!("Lv {}/{}", upgrade.owned, max),
+                        None => format!("Lv {}", upgrade.owned),
+                    };
+                    let cost = if upgrade.is_maxed() {
+                        Span::styled("Maxed", theme.fg(theme.muted))
+                    } else {
+                        let affordable = upgrade.can_afford(state.prestige_points);
+                        let color = if affordable { theme.affordable } else { theme.unaffordable };
+                        Span::styled(format!("Cost: {}{} points", affordability_mark(app, affordable), upgrade.current_cost()), theme.fg(color))
+                    };
+                    ListItem::new(vec![
+                        Line::from(vec![
+                            Span::styled(upgrade.name.clone(), theme.fg(theme.heading).add_modifier(Modifier::BOLD)),
+                            Span::styled(format!("  {}", level), theme.fg(theme.muted)),
+                        ]),
+                        Line::from(Span::styled(upgrade.description.clone(), theme.fg(theme.text))),
+                        Line::from(cost),
+                        Line::from(""),
+                    ])
+                })
+                .collect();
+
+            app.list_area = prestige_chunks[1];
+            app.list_row_heights = shop_items.iter().map(|item| item.height() as u16).collect();
+            let shop = List::new(shop_items)
+                .block(pane_block(app, Focus::List, "Prestige Shop"))
+                .highlight_style(theme.bg(theme.highlight).add_modifier(Modifier::BOLD))
+                .highlight_symbol("> ");
+            f.render_stateful_widget(shop, prestige_chunks[1], &mut app.list_state);
+        }
+    }
+}
+
+// Tutorial page in a box along the bottom, leaving the rest of the game visible
+fn render_tutorial(f: &mut Frame, app: &App, step: usize) {
+    let theme = app.theme;
+    let (title, text) = TUTORIAL_STEPS[step];
+    let hint = if step + 1 < TUTORIAL_STEPS.len() { " Enter: next | Esc: skip " } else { " Enter: done " };
+    let lines = vec![Line::from(Span::styled(text, theme.fg(theme.text)))];
+
+    let width = 64.min(f.area().width);
+    let inner_width = width.saturating_sub(2).max(1) as usize;
+    let height: usize = lines.iter().map(|line| line.width().div_ceil(inner_width).max(1)).sum();
+    let height = (height as u16 + 2).min(f.area().height);
+    let area = Rect {
+        x: f.area().x + (f.area().width - width) / 2,
+        // Sits just above the footer
+        y: f.area().bottom().saturating_sub(height + 3).max(f.area().y),
+        width,
+        height,
+    };
+    let block = bordered_block(&theme, format!("Tutorial {}/{}: {}", step + 1, TUTORIAL_STEPS.len(), title))
+        .title_bottom(Line::from(Span::styled(hint, theme.fg(theme.muted))).centered());
+    let popup = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// Title and contents of a scrollable document
+fn document_text(app: &App, document: Document) -> (&'static str, Vec<Line<'static>>) {
+    let theme = app.theme;
+    match document {
+        Document::News => ("What's New", NEWS.lines().map(Line::from).collect()),
+        Document::PrestigeHistory => {
+            let state = &app.game_state;
+            if state.prestige_history.is_empty() {
+                return ("Prestige History", vec![Line::from(Span::styled("No prestiges yet", theme.fg(theme.muted)))]);
+            }
+            // Newest first, numbered by how many prestiges came before
+            let first_number = (state.prestige_count as usize).saturating_sub(state.prestige_history.len()) + 1;
+            let lines = state.prestige_history.iter().enumerate().rev()
+                .map(|(index, record)| Line::from(vec![
+                    Span::styled(format!("#{:<4}", first_number + index), theme.fg(theme.heading)),
+                    Span::styled(format!("{:<12}", format_ago(state.clock.unix_time().saturating_sub(record.at))), theme.fg(theme.muted)),
+                    Span::styled(format!("{} gold", state.format(record.gold_earned)), theme.fg(theme.gold)),
+                    Span::raw(" -> "),
+                    Span::styled(format!("+{} points", record.points), theme.fg(theme.rate)),
+                ]))
+                .collect();
+            ("Prestige History", lines)
+        }
+    }
+}
+
+// Where a document popup goes: as tall as its text, but never taller than the terminal
+fn document_area(area: Rect, lines: &[Line]) -> Rect {
+    let width = DOCUMENT_WIDTH.min(area.width);
+    let height = (wrapped_height(lines, width) + 2).min(area.height.saturating_sub(2));
+    centered_rect(width, height, area)
+}
+
+// Rows `lines` take once wrapped inside a bordered popup `width` cells wide
+fn wrapped_height(lines: &[Line], width: u16) -> u16 {
+    let inner_width = width.saturating_sub(2).max(1) as usize;
+    lines.iter().map(|line| line.width().div_ceil(inner_width).max(1)).sum::<usize>() as u16
+}
+
+// Furthest a document can scroll before its last line leaves the bottom edge
+fn max_document_scroll(area: Rect, lines: &[Line]) -> u16 {
+    let popup = document_area(area, lines);
+    wrapped_height(lines, popup.width).saturating_sub(popup.height.saturating_sub(2))
+}
+
+fn render_document(f: &mut Frame, app: &App, document: Document, scroll: u16) {
+    let theme = app.theme;
+    let (title, lines) = document_text(app, document);
+    let area = document_area(f.area(), &lines);
+    let hint = if max_document_scroll(f.area(), &lines) > 0 { " Up/Down to scroll, Esc to close " } else { " Esc to close " };
+    let text = Paragraph::new(lines)
+        .block(bordered_block(&theme, title).title_bottom(Line::from(Span::styled(hint, theme.fg(theme.muted))).centered()))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    f.render_widget(Clear, area);
+    f.render_widget(text, area);
+}
+
+fn render_overlay(f: &mut Frame, app: &App, overlay: &Overlay) {
+    let theme = app.theme;
+    if let Overlay::Document { document, scroll } = overlay {
+        render_document(f, app, *document, *scroll);
+        return;
+    }
+    let (title, width, lines): (&str, u16, Vec<Line>) = match overlay {
+        Overlay::Welcome { offline, daily } => {
+            let mut lines = vec![Line::from("")];
+            if let Some(report) = offline {
+                let away = if report.credited < report.away {
+                    format!("You were away {} but earned for {}", format_duration(report.away), format_duration(report.credited))
+                } else {
+                    format!("You were away {}", format_duration(report.away))
+                };
+                lines.push(Line::from(away));
+                lines.push(Line::from(vec![
+                    Span::raw("Your mines produced "),
+                    Span::styled(format!("{} gold", app.game_state.format(report.gold)), theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+                ]));
+                lines.push(Line::from(""));
+            }
+            if let Some(bonus) = daily {
+                lines.push(Line::from(Span::styled(
+                    format!("Day {} streak!", bonus.streak),
+                    theme.fg(theme.event).add_modifier(Modifier::BOLD),
+                )));
+                lines.push(Line::from(vec![
+                    Span::raw("Today's bonus: "),
+                    Span::styled(format!("{} gold", app.game_state.format(bonus.gold)), theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+                ]));
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled("Press any key to continue", theme.fg(theme.muted))));
+            ("Welcome Back", 50, lines)
+        }
+        Overlay::Profiles { names, selected } => {
+            let mut lines = vec![Line::from("")];
+            for (index, name) in names.iter().enumerate() {
+                lines.push(if index == *selected {
+                    Line::from(Span::styled(format!("> {} <", name), theme.fg(theme.gold).add_modifier(Modifier::BOLD)))
+                } else {
+                    Line::from(name.clone())
+                });
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("Up/Down to choose, Enter to play", theme.fg(theme.muted))));
+            ("Choose a Profile", 40, lines)
+        }
+        Overlay::Document { .. } => unreachable!("drawn by render_document"),
+        Overlay::ConfirmReset => ("Reset", 44, vec![
+            Line::from(""),
+            Line::from(Span::styled("Reset all progress? (y/n)", theme.fg(theme.unaffordable).add_modifier(Modifier::BOLD))),
+            Line::from(""),
+            Line::from(Span::styled("This deletes your save file.", theme.fg(theme.muted))),
+        ]),
+        Overlay::ConfirmQuit => ("Quit", 36, vec![
+            Line::from(""),
+            Line::from(Span::styled("Quit? (y/n)", theme.fg(theme.gold).add_modifier(Modifier::BOLD))),
+            Line::from(""),
+            Line::from(Span::styled("Your progress will be saved.", theme.fg(theme.muted))),
+        ]),
+        Overlay::ConfirmPrestige => {
+            let state = &app.game_state;
+            let gained = state.prestige_points_available();
+            let after = prestige::points_multiplier(state.prestige_points.saturating_add(gained));
+            ("Prestige", 56, vec![
+                Line::from(""),
+                Line::from(Span::styled(
+                    format!("Prestige now for +{} points? (y/n)", gained),
+                    theme.fg(theme.gold).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(format!("Points: {} -> {}", state.prestige_points, state.prestige_points.saturating_add(gained))),
+                Line::from(vec![
+                    Span::raw("Multiplier: "),
+                    Span::styled(
+                        format!("x{:.2} -> x{:.2}", state.prestige_multiplier(), after),
+                        theme.fg(theme.rate),
+                    ),
+                ]),
+                Line::from(""),
+                Line::from(Span::styled("Gold and upgrades reset; achievements and the prestige shop stay.", theme.fg(theme.muted))),
+            ])
+        }
+        Overlay::Export(encoded) => ("Export Save", 70, vec![
+            Line::from(Span::styled("Copy this string to back up or share your progress:", theme.fg(theme.muted))),
+            Line::from(""),
+            Line::from(Span::styled(encoded.clone(), theme.fg(theme.gold))),
+            Line::from(""),
+            Line::from(Span::styled("Esc to close", theme.fg(theme.muted))),
+        ]),
+        Overlay::Share(summary) => ("Share Score", 70, vec![
+            Line::from(Span::styled("Copy this line to show off your mine:", theme.fg(theme.muted))),
+            Line::from(""),
+            Line::from(Span::styled(summary.clone(), theme.fg(theme.gold))),
+            Line::from(""),
+            Line::from(Span::styled("Esc to close", theme.fg(theme.muted))),
+        ]),
+        Overlay::Import { input, error } => {
+            let mut lines = vec![
+                Line::from(Span::styled("Paste a save string and press Enter (Esc cancels):", theme.fg(theme.muted))),
+                Line::from(""),
+                Line::from(Span::styled(format!("{}_", input), theme.fg(theme.gold))),
+            ];
+            if let Some(error) = error {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(format!("Import failed: {}", error), theme.fg(theme.unaffordable))));
+            }
+            ("Import Save", 70, lines)
+        }
+        Overlay::Details(name) => {
+            let state = &app.game_state;
+            let Some(upgrade) = state.upgrades.iter().find(|u| &u.name == name) else {
+                return;
+            };
+            let unit = match upgrade.upgrade_type {
+                UpgradeType::Passive => "/sec",
+                UpgradeType::Click => "/click",
+                UpgradeType::AutoClick => " clicks/sec",
+            };
+            let next = Upgrade { owned: upgrade.owned + 1, ..upgrade.clone() };
+            let row = |label: &str, value: String, color| Line::from(vec![
+                Span::raw(format!("{}: ", label)),
+                Span::styled(value, theme.fg(color)),
+            ]);
+
+            let mut lines = vec![
+                Line::from(Span::styled(upgrade.description.clone(), theme.fg(theme.muted))),
+                Line::from(""),
+                row("Owned", format!("{} (most ever: {})", upgrade.owned, upgrade.max_owned), theme.text),
+                row("Cost", format!("{} now, then {}", state.format(upgrade.current_cost()), state.format(next.current_cost())), theme.gold),
+                row("Production", format!(
+                    "{}{} each, {}{} for all {}",
+                    state.format(upgrade.unit_production()), unit, state.format(upgrade.current_production()), unit, upgrade.owned,
+                ), theme.rate),
+            ];
+            if upgrade.upgrade_type != UpgradeType::Click {
+                lines.push(row("Made so far", format!("{} gold", state.format(upgrade.lifetime_gold)), theme.total));
+            }
+            lines.push(row("Milestone", format!(
+                "{}/{} for {}x ({} for {} more)",
+                upgrade.owned,
+                upgrade.next_milestone(),
+                next_milestone_multiplier(state, upgrade),
+                state.format(upgrade.bulk_cost(upgrade.copies_to_milestone())),
+                upgrade.copies_to_milestone(),
+            ), theme.text));
+            for synergy in SYNERGIES.iter().filter(|s| s.target == upgrade.name) {
+                lines.push(row("Boosted by", format!("{} (+{:.0}% per copy, now +{:.0}%)", synergy.source, synergy.bonus * 100.0, upgrade.synergy_bonus * 100.0), theme.event));
+            }
+            for synergy in SYNERGIES.iter().filter(|s| s.source == upgrade.name) {
+                lines.push(row("Boosts", format!("{} (+{:.0}% per copy)", synergy.target, synergy.bonus * 100.0), theme.event));
+            }
+            if let Some(condition) = upgrade.unlock_condition.as_ref().filter(|_| !state.is_unlocked(upgrade)) {
+                lines.push(row("Locked", unlock_requirement(state, condition), theme.unaffordable));
+            }
+            if upgrade.auto_buy {
+                lines.push(row("Auto-buy", "on".to_string(), theme.event));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("Esc to close", theme.fg(theme.muted))));
+            (upgrade.name.as_str(), 64, lines)
+        }
+        Overlay::SetGoal { input, error } => {
+            let mut lines = vec![
+                Line::from(Span::styled("Gold to aim for, e.g. 5000, 2.5M or 1e9 (Esc cancels):", theme.fg(theme.muted))),
+                Line::from(""),
+                Line::from(Span::styled(format!("{}_", input), theme.fg(theme.gold))),
+            ];
+            if let Some(error) = error {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(error.clone(), theme.fg(theme.unaffordable))));
+            }
+            ("Set Goal", 60, lines)
+        }
+    };
+
+    // Account for wrapping so long lines (like save strings) still fit
+    let width = width.min(f.area().width);
+    let inner_width = width.saturating_sub(2).max(1) as usize;
+    let height: usize = lines.iter().map(|line| line.width().div_ceil(inner_width).max(1)).sum();
+    let area = centered_rect(width, height as u16 + 2, f.area());
+    let popup = Paragraph::new(lines)
+        .block(bordered_block(&theme, title))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// Timings from --debug, drawn over the bottom-right corner
+fn render_perf(f: &mut Frame, app: &App, perf: &PerfStats) {
+    let theme = app.theme;
+    let millis = |duration: Duration| format!("{:.2}ms", duration.as_secs_f64() * 1000.0);
+    let lines = vec![
+        Line::from(format!("Draw {}", millis(perf.draw_time))),
+        Line::from(format!("Tick {}", millis(perf.tick_time))),
+        Line::from(format!("FPS  {}", perf.fps())),
+    ];
+
+    let width = (lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2).min(f.area().width);
+    let height = (lines.len() as u16 + 2).min(f.area().height);
+    let area = Rect {
+        x: f.area().right() - width,
+        y: f.area().bottom() - height,
+        width,
+        height,
+    };
+    let popup = Paragraph::new(lines).style(theme.fg(theme.muted)).block(bordered_block(&theme, "Perf"));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// Full-precision readout in the top-right corner; the rest of the UI keeps its rounded numbers
+fn render_precise_values(f: &mut Frame, app: &App) {
+    let theme = app.theme;
+    let state = &app.game_state;
+    let value_line = |label: &str, value: f64, color| Line::from(vec![
+        Span::raw(format!("{:<7}", label)),
+        Span::styled(format!("{:.6}", value), theme.fg(color)),
+    ]);
+    let lines = vec![
+        value_line("Gold", state.gold, theme.gold),
+        value_line("Rate", state.gold_per_second, theme.rate),
+        value_line("Click", state.click_power, theme.click),
+    ];
+
+    let width = (lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2).min(f.area().width);
+    let height = (lines.len() as u16 + 2).min(f.area().height);
+    let area = Rect {
+        x: f.area().right() - width,
+        y: f.area().y,
+        width,
+        height,
+    };
+    let popup = Paragraph::new(lines).block(bordered_block(&theme, "Precise"));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// Text bar like "[#####-----] 50%", filled to `fraction` of `width` cells
+fn progress_bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction * width as f64).floor() as usize).min(width);
+    format!("[{}{}] {:.0}%", "#".repeat(filled), "-".repeat(width - filled), (fraction * 100.0).floor())
+}
+
+// Compact "2h 5m" style duration, dropping the smallest unit once hours are involved
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+// How long ago something happened, in days once it's been a day or more
+fn format_ago(secs: u64) -> String {
+    match secs / 86_400 {
+        0 => format!("{} ago", format_duration(Duration::from_secs(secs))),
+        1 => "1 day ago".to_string(),
+        days => format!("{} days ago", days),
+    }
+}
+
+// Rect of the given size centered in `area`, shrunk to fit if `area` is smaller
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn bordered_block<'a>(theme: &Theme, title: impl Into<Line<'a>>) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.fg(theme.border))
+        .title(title)
+}
+
+// Border for one of the panes focus moves between; the focused one gets a bold double
+// line, which still shows without colors
+fn pane_block<'a>(app: &App, pane: Focus, title: impl Into<Line<'a>>) -> Block<'a> {
+    let block = bordered_block(&app.theme, title);
+    if app.focus == pane {
+        block.border_type(BorderType::Double).border_style(app.theme.fg(app.theme.gold).add_modifier(Modifier::BOLD))
+    } else {
+        block
+    }
+}
+
+// Left column shared by every tab - click area and progress
+// Returns the clickable mining panel's area
+fn render_mining_column(f: &mut Frame, app: &App, area: Rect) -> Rect {
+    let theme = app.theme;
+    let left_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(5)].as_ref())
+        .split(area);
+
+    let cooldown = app.game_state.click_cooldown;
+    let cooldown_note = if cooldown.is_zero() {
+        String::new()
+    } else {
+        format!(" ({}s cooldown)", cooldown.as_secs_f64())
+    };
+
+    let mut mining_lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("CLICK FOR GOLD!", theme.fg(theme.gold).add_modifier(Modifier::BOLD))
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Press "),
+            Span::styled("SPACE", theme.fg(theme.rate).add_modifier(Modifier::BOLD)),
+            Span::raw(" to mine +"),
+            Span::styled(app.game_state.format(app.game_state.click_power), theme.fg(theme.gold).add_modifier(Modifier::BOLD)),
+            Span::raw(" gold"),
+            Span::raw(cooldown_note),
+        ]),
+        Line::from(vec![
+            Span::raw("Average with crits: +"),
+            Span::styled(app.game_state.format(app.game_state.expected_click_value()), theme.fg(theme.click)),
+            Span::styled(
+                format!(" ({:.0}% chance of {}x)", app.game_state.crit_chance * 100.0, app.game_state.crit_multiplier),
+                theme.fg(theme.muted),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Or just wait and earn "),
+            Span::styled(format!("{} gold/sec", app.game_state.format(app.game_state.gold_per_second)), theme.fg(theme.rate)),
+        ]),
+        Line::from(""),
+        last_click_line(app),
+    ];
+    if app.game_state.combo > 0 {
+        mining_lines.push(Line::from(""));
+        mining_lines.push(Line::from(vec![
+            Span::styled(format!("Combo x{}", app.game_state.combo), theme.fg(theme.event).add_modifier(Modifier::BOLD)),
+            Span::raw(format!(" ({:.1}x gold per click)", app.game_state.combo_multiplier())),
+        ]));
+    }
+    if app.game_state.auto_clicks_per_second > 0.0 {
+        mining_lines.push(Line::from(""));
+        mining_lines.push(Line::from(vec![
+            Span::raw("Auto-clickers: "),
+            Span::styled(format!("{} clicks/sec", app.game_state.format(app.game_state.auto_clicks_per_second)), theme.fg(theme.click)),
+        ]));
+    }
+
+    let click_area = Paragraph::new(mining_lines)
+    .block(pane_block(app, Focus::Mining, "Mining"))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    f.render_widget(click_area, left_chunks[0]);
+
+    // The label shows the true value while the bar glides toward it
+    let (_, label) = gold_progress(&app.game_state);
+    let gauge = Gauge::default()
+        .block(bordered_block(&theme, "Gold Progress"))
+        .gauge_style(theme.fg(theme.gold))
+        .ratio(app.displayed_progress.clamp(0.0, 1.0))
+        .label(label);
+    f.render_widget(gauge, left_chunks[1]);
+
+    left_chunks[0]
+}
+
+// Progress toward the cheapest upgrade that's still out of reach, with its gauge label
+fn gold_progress(state: &GameState) -> (f64, String) {
+    match state.next_purchase_goal() {
+        Some(goal) => {
+            let progress = (state.gold / goal.current_cost()).clamp(0.0, 1.0);
+            (progress, format!("{}: {:.1}%", goal.name, progress * 100.0))
+        }
+        None => (1.0, "All affordable!".to_string()),
+    }
+}
+
+// "Last click: 0.3s ago (ready)", red while the cooldown would swallow a press
+fn last_click_line(app: &App) -> Line<'static> {
+    let theme = app.theme;
+    let state = &app.game_state;
+    let since = if state.total_clicks == 0 {
+        "never".to_string()
+    } else {
+        format!("{:.1}s ago", state.time_since_last_click().as_secs_f64())
+    };
+    let remaining = state.click_cooldown_remaining();
+    let (status, color) = if remaining.is_zero() {
+        ("(ready)".to_string(), theme.affordable)
+    } else {
+        // Round up so it never reads "0.0s" while still cooling down
+        (format!("(cooldown {:.1}s)", (remaining.as_secs_f64() * 10.0).ceil() / 10.0), theme.unaffordable)
+    };
+    Line::from(vec![
+        Span::raw(format!("Last click: {} ", since)),
+        Span::styled(status, theme.fg(color)),
+    ])
+}
+
+// Draws the golden nugget, if any, and returns its clickable area
+fn render_nugget(f: &mut Frame, app: &App) -> Rect {
+    const LABEL: &str = "<$>";
+    let Some(nugget) = &app.game_state.nugget else {
+        return Rect::default();
+    };
+    let inner = app.mining_area.inner(Margin::new(1, 1));
+    let width = LABEL.len() as u16;
+    if inner.width < width || inner.height == 0 {
+        return Rect::default();
+    }
+
+    let area = Rect {
+        x: inner.x + ((inner.width - width) as f64 * nugget.x) as u16,
+        y: inner.y + ((inner.height - 1) as f64 * nugget.y) as u16,
+        width,
+        height: 1,
+    };
+    let mut style = app.theme.fg(app.theme.gold).add_modifier(Modifier::BOLD | Modifier::REVERSED);
+    // Blink for the last couple of seconds as a warning
+    if NUGGET_LIFETIME - nugget.age < 2.0 {
+        style = style.add_modifier(Modifier::SLOW_BLINK);
+    }
+    f.render_widget(Paragraph::new(Span::styled(LABEL, style)), area);
+    area
+}
+
+// Each gain drifts up from the bottom of the mining panel and dims as it ages
+fn render_floating_gains(f: &mut Frame, app: &App) {
+    let inner = app.mining_area.inner(Margin::new(1, 1));
+    if inner.height < 3 {
+        return;
+    }
+    let rise = inner.height.saturating_sub(2).min(4);
+
+    for (outcome, at) in &app.floating_gains {
+        let age = at.elapsed().as_secs_f64() / FLOATING_GAIN_DURATION.as_secs_f64();
+        if age >= 1.0 {
+            continue;
+        }
+        let text = if outcome.crit {
+            format!("+{} CRIT!", app.game_state.format(outcome.gold))
+        } else {
+            format!("+{}", app.game_state.format(outcome.gold))
+        };
+        let style = match age {
+            a if a < 0.33 => app.theme.fg(app.theme.gold).add_modifier(Modifier::BOLD),
+            a if a < 0.66 => app.theme.fg(app.theme.gold),
+            _ => app.theme.fg(app.theme.muted).add_modifier(Modifier::DIM),
+        };
+
+        let width = (text.len() as u16).min(inner.width);
+        let area = Rect {
+            x: inner.x + (inner.width - width) / 2,
+            y: inner.bottom() - 1 - (age * rise as f64) as u16,
+            width,
+            height: 1,
+        };
+        f.render_widget(Paragraph::new(Span::styled(text, style)), area);
+    }
+}
+
+async fn run_app<B: Backend + Write>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
+    let mut update_interval = interval(app.tick_interval);
+    update_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    // Terminal input blocks, so read it on its own thread instead of polling
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if event_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut last_draw: Option<Instant> = None;
+    loop {
+        // Changes wait for the frame cap so slow links aren't flooded; the game itself
+        // keeps ticking at full rate either way
+        let frame_due = last_draw.is_none_or(|drawn| drawn.elapsed() >= app.min_frame_time);
+        if (app.dirty && frame_due) || last_draw.is_none_or(|drawn| drawn.elapsed() >= IDLE_REDRAW_INTERVAL) {
+            let draw_started = app.perf.is_some().then(Instant::now);
+            terminal.draw(|f| ui(f, app))?;
+            if let (Some(perf), Some(started)) = (&mut app.perf, draw_started) {
+                perf.record_draw(started);
+            }
+            app.dirty = false;
+            last_draw = Some(Instant::now());
+        }
+        // Written between frames so the control byte never lands inside one
+        if std::mem::take(&mut app.bell_pending) {
+            let backend = terminal.backend_mut();
+            backend.write_all(b"\x07")?;
+            Write::flush(backend)?;
+        }
+
+        let next_frame = last_draw.map_or_else(Instant::now, |drawn| drawn + app.min_frame_time);
+        // select! polls the branches in random order, so a flood of key or mouse events can
+        // delay a tick but never starve it; and since update() pays for all the time since
+        // the last one, a late tick still credits every second
+        tokio::select! {
+            // Wakes up to draw a change held back by the frame cap
+            _ = sleep_until(next_frame.into()), if app.dirty => {}
+
+            _ = update_interval.tick() => {
+                // Gold math still runs every tick; only drawing is skipped when idle
+                let tick_started = app.perf.is_some().then(Instant::now);
+                app.on_tick();
+                if let (Some(perf), Some(started)) = (&mut app.perf, tick_started) {
+                    perf.record_tick(started);
+                }
+            }
+            
+            Some(event) = event_rx.recv() => {
+                match event {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => app.on_key(key.code),
+                    Event::Mouse(mouse) => app.on_mouse(mouse),
+                    Event::Resize(..) => {
+                        app.on_resize();
+                        // Redraw right away, even inside the frame cap: a frame laid out for
+                        // the old size is garbled until the next one replaces it
+                        last_draw = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if app.should_quit {
+            return Ok(());
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match cli::Options::parse() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return ExitCode::SUCCESS;
+    }
+    if let Some(seconds) = options.simulate {
+        simulate::run(seconds, &options);
+        return ExitCode::SUCCESS;
+    }
+    // Checked before raw mode, which a pipe or dumb terminal would be left stuck in or garbled by
+    let term = std::env::var("TERM").ok();
+    if let Some(reason) = unsupported_terminal(term.as_deref(), io::stdin().is_terminal(), io::stdout().is_terminal()) {
+        eprintln!("error: {}; the game needs an interactive terminal with cursor movement and colors (try --simulate to run headless)", reason);
+        return ExitCode::FAILURE;
+    }
+
+    // Without an explicit location, any profile saves lying around are offered at startup
+    let (save_path, profiles) = match save::explicit_save_path(options.save_path.as_deref(), options.profile.as_deref()) {
+        Some(path) => (path, Vec::new()),
+        None => (save::default_save_path(), save::list_profiles()),
+    };
+    if let Err(err) = save::ensure_writable(&save_path) {
+        eprintln!("error: can't write the save file {}: {}", save_path.display(), err);
+        return ExitCode::FAILURE;
+    }
+
+    // Bad bindings shouldn't stop the game; these stay visible once the alternate screen closes
+    let (keymap, keymap_warnings) = match paths::config_dir() {
+        Some(dir) => Keymap::load(&dir.join("keybinds.toml")),
+        None => (Keymap::default(), Vec::new()),
+    };
+    for warning in &keymap_warnings {
+        eprintln!("warning: keybinds.toml: {}", warning);
+    }
+    let (achievements, achievement_warnings) = match paths::config_dir() {
+        Some(dir) => content::load_achievements(&dir.join("achievements.json")),
+        None => (content::default_achievements(), Vec::new()),
+    };
+    for warning in &achievement_warnings {
+        eprintln!("warning: achievements.json: {}", warning);
+    }
+    let preferences_path = paths::config_dir().map(|dir| dir.join("preferences.json"));
+    let (preferences, preference_warnings) = match &preferences_path {
+        Some(path) => Preferences::load(path),
+        None => (Preferences::default(), Vec::new()),
+    };
+    for warning in &preference_warnings {
+        eprintln!("warning: preferences.json: {}", warning);
+    }
+
+    // Put the terminal back before the panic message prints, or it lands garbled in raw mode
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show);
+        default_hook(info);
+    }));
+
+    let mut terminal = match setup_terminal() {
+        Ok(terminal) => terminal,
+        Err(err) => {
+            eprintln!("error: couldn't set up the terminal: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Create app and run it
+    let started_at = Instant::now();
+    let mut app = App::new(save_path, profiles, &options, keymap, achievements);
+    app.use_preferences(preferences, preferences_path);
+    let warning_count = keymap_warnings.len() + achievement_warnings.len() + preference_warnings.len();
+    if warning_count > 0 && app.toast.is_none() {
+        app.show_toast(format!("{} config problem(s); details are printed on exit", warning_count));
+    }
+    let res = run_app(&mut terminal, &mut app).await;
+
+    // Everything below goes to stderr once the normal screen is back, so none of it is lost
+    // and scripts can tell from the exit code that something went wrong
+    let mut failed = false;
+    if let Err(err) = restore_terminal(&mut terminal) {
+        eprintln!("error: couldn't restore the terminal (try running `reset`): {}", err);
+        failed = true;
+    }
+    if let Err(err) = res {
+        eprintln!("error: lost contact with the terminal while playing: {}", err);
+        failed = true;
+    }
+
+    for notice in &app.notices {
+        eprintln!("{}", notice);
+    }
+
+    // Progress is saved even after an error above
+    if let Err(err) = app.save() {
+        eprintln!("error: failed to save progress to {}: {}", app.save_path.display(), err);
+        failed = true;
+    }
+    if let Some(path) = &options.stats_json
+        && let Err(err) = save::write_stats(&app.game_state, started_at.elapsed(), path)
+    {
+        eprintln!("error: failed to write stats to {}: {}", path.display(), err);
+        failed = true;
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+// Why the full-screen UI can't run here, if it can't. Windows consoles don't set TERM, so
+// only an explicit "dumb" counts against it
+fn unsupported_terminal(term: Option<&str>, stdin_is_terminal: bool, stdout_is_terminal: bool) -> Option<String> {
+    if term == Some("dumb") {
+        Some("TERM is set to \"dumb\"".to_string())
+    } else if !stdin_is_terminal || !stdout_is_terminal {
+        Some("input and output must both be a terminal, not a pipe or file".to_string())
+    } else {
+        None
+    }
+}
+
+// Raw mode, alternate screen and mouse capture; undoes raw mode again if the rest fails
+fn setup_terminal() -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    let result = execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
+        .and_then(|()| Terminal::new(CrosstermBackend::new(stdout)));
+    if result.is_err() {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+    }
+    result
+}
+
+// Tries every step even when one fails, and reports the first failure
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
+    let raw_mode = disable_raw_mode();
+    let screen = execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture);
+    let cursor = terminal.show_cursor();
+    raw_mode.and(screen).and(cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::MockClock;
+
+    #[test]
+    fn click_respects_cooldown() {
+        let clock = MockClock::new();
+        let mut state = GameState::with_clock(Box::new(clock.clone()));
+
+        state.click_for_gold();
+        state.click_for_gold();
+        assert_eq!(state.total_clicks, 1);
+
+        clock.advance(Duration::from_millis(499));
+        state.click_for_gold();
+        assert_eq!(state.total_clicks, 1);
+
+        clock.advance(Duration::from_millis(1));
+        state.click_for_gold();
+        assert_eq!(state.total_clicks, 2);
+    }
+
+    fn pickaxe(owned: u64) -> Upgrade {
+        let mut upgrade = Upgrade::new("Pickaxe", "", 10.0, 1.15, 0.1, UpgradeType::Passive);
+        upgrade.owned = owned;
+        upgrade
+    }
+
+    #[test]
+    fn cost_grows_geometrically() {
+        assert!((pickaxe(0).current_cost() - 10.0).abs() < 1e-9);
+        assert!((pickaxe(1).current_cost() - 11.5).abs() < 1e-9);
+        assert!((pickaxe(5).current_cost() - 10.0 * 1.15f64.powi(5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn production_is_linear_in_owned() {
+        assert_eq!(pickaxe(0).current_production(), 0.0);
+        assert!((pickaxe(1).current_production() - 0.1).abs() < 1e-9);
+        assert!((pickaxe(10).current_production() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn can_afford_at_exactly_the_cost() {
+        let upgrade = pickaxe(1);
+        assert!(upgrade.can_afford(11.5));
+        assert!(!upgrade.can_afford(11.49));
+    }
+
+    #[test]
+    fn purchase_returns_cost_before_increment() {
+        let mut upgrade = pickaxe(1);
+        let cost = upgrade.purchase();
+        assert!((cost - 11.5).abs() < 1e-9);
+        assert_eq!(upgrade.owned, 2);
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tui-idle-game-{}-test", name))
+    }
+
+    // An App on its own save in a temp directory, with the tutorial already dismissed
+    fn test_app(name: &str, options: &cli::Options) -> App {
+        let mut app = App::new(test_dir(name).join("save.json"), Vec::new(), options, Keymap::default(), content::default_achievements());
+        app.tutorial_step = None;
+        app
+    }
+
+    fn render(app: &mut App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn renders_gold_and_selection() {
+        let mut app = test_app("render", &cli::Options::default());
+        // No random crits, so the click is worth exactly 1 gold
+        app.game_state.crit_chance = 0.0;
+
+        app.on_key(KeyCode::Char(' '));
+        app.on_key(KeyCode::Down);
+        app.on_tick();
+        let screen = render(&mut app, 120, 40);
+
+        assert!(screen.contains("Gold: 1.00"), "{}", screen);
+        assert!(screen.contains("> Shovel (0)"), "{}", screen);
+        assert!(!screen.contains("> Pickaxe"), "{}", screen);
+
+        // Small terminals fall back to the compact layout
+        let screen = render(&mut app, 60, 16);
+        assert!(screen.contains("1.00 gold"), "{}", screen);
+        assert!(!screen.contains("Mining"), "{}", screen);
+
+        let screen = render(&mut app, 30, 10);
+        assert!(screen.contains("Terminal too small"), "{}", screen);
+        render(&mut app, 0, 0);
+    }
+
+    #[test]
+    fn details_popup_describes_the_selected_upgrade() {
+        let mut app = test_app("details", &cli::Options::default());
+        app.game_state.select_next();
+        app.on_key(KeyCode::Char('w'));
+        let screen = render(&mut app, 100, 40);
+        assert!(screen.contains("Shovel"), "{}", screen);
+        assert!(screen.contains("Milestone: 0/25 for 2x"), "{}", screen);
+
+        // Enter doesn't buy through the popup
+        app.game_state.gold = 1e6;
+        app.on_key(KeyCode::Enter);
+        assert_eq!(app.game_state.upgrades[1].owned, 0);
+        app.on_key(KeyCode::Esc);
+        assert!(app.overlay.is_none());
+    }
+
+    #[test]
+    fn accrual_doesnt_depend_on_tick_size() {
+        let earned_with_ticks_of = |millis: u64| {
+            let clock = MockClock::new();
+            let mut state = GameState::with_clock(Box::new(clock.clone()));
+            state.event_chance = 0.0;
+            // 1 gold/sec stays short of every achievement, so the rate never changes
+            state.upgrades[0].owned = 10;
+            for _ in 0..10_000 / millis {
+                clock.advance(Duration::from_millis(millis));
+                state.update();
+            }
+            state.total_gold_earned
+        };
+
+        let expected = earned_with_ticks_of(100);
+        assert!(expected > 0.0);
+        for millis in [10, 50, 250, 1000] {
+            let earned = earned_with_ticks_of(millis);
+            assert!((earned - expected).abs() < 1e-9 * expected, "{}ms ticks: {} vs {}", millis, earned, expected);
+        }
+    }
+
+    #[test]
+    fn keys_between_ticks_dont_lose_passive_gold() {
+        let clock = MockClock::new();
+        let mut app = test_app("accrual", &cli::Options::default());
+        // Achievement rewards would change the rate partway through
+        app.achievements = Vec::new();
+        let mut state = GameState::with_clock(Box::new(clock.clone()));
+        state.upgrades[0].owned = 50;
+        state.gold = 1e6;
+        state.event_chance = 0.0;
+        app.replace_game_state(state);
+        app.on_tick();
+        let rate = app.game_state.gold_per_second;
+        let earned_before = app.game_state.total_gold_earned;
+
+        // Click upgrades don't touch passive income, so the rate holds while we buy them
+        app.on_key(KeyCode::Char('2'));
+        for step in 0..50 {
+            clock.advance(Duration::from_millis(20));
+            app.on_key([KeyCode::Down, KeyCode::Enter, KeyCode::Up, KeyCode::Enter][step % 4]);
+            if step % 5 == 4 {
+                app.on_tick();
+            }
+        }
+        app.on_tick();
+
+        assert!(app.game_state.click_power > 10.0);
+        assert_eq!(app.game_state.gold_per_second, rate);
+        assert!((app.game_state.total_gold_earned - earned_before - rate).abs() < 1e-6);
+    }
+
+    #[test]
+    fn header_gold_drops_decimals_past_the_threshold() {
+        let options = cli::Options { whole_gold_above: Some(100.0), ..cli::Options::default() };
+        let mut app = test_app("whole-gold", &options);
+        app.displayed_gold = 99.99;
+        assert_eq!(header_gold(&app), "99.99");
+        app.displayed_gold = 123.99;
+        assert_eq!(header_gold(&app), "123");
+        app.displayed_gold = 12_345.67;
+        assert_eq!(header_gold(&app), "12.35K");
+        app.game_state.number_format = NumberFormat::Grouped;
+        assert_eq!(header_gold(&app), "12,345");
+    }
+
+    #[test]
+    fn header_gold_counts_up_but_drops_at_once() {
+        let mut app = test_app("counter", &cli::Options::default());
+        app.game_state.gold = 1000.0;
+        app.gold_updated_at = Instant::now() - Duration::from_millis(50);
+        app.ease_gold();
+        assert!(app.displayed_gold > 0.0 && app.displayed_gold < 1000.0, "{}", app.displayed_gold);
+
+        app.gold_updated_at = Instant::now() - Duration::from_secs(2);
+        app.ease_gold();
+        assert!(app.displayed_gold > 999.0, "{}", app.displayed_gold);
+
+        app.game_state.gold = 10.0;
+        app.ease_gold();
+        assert_eq!(app.displayed_gold, 10.0);
+    }
+
+    #[test]
+    fn selling_refunds_half_the_last_copy() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.upgrades[0].owned = 3;
+        state.recompute_rates();
+        let rate = state.gold_per_second;
+        let last_copy = Upgrade { owned: 2, ..state.upgrades[0].clone() }.current_cost();
+
+        assert_eq!(state.sell_selected(), Some(last_copy * SELL_REFUND));
+        assert_eq!(state.gold, last_copy * SELL_REFUND);
+        assert_eq!(state.upgrades[0].owned, 2);
+        assert!(state.gold_per_second < rate);
+        assert_eq!(state.total_gold_earned, 0.0);
+
+        state.sell_selected();
+        state.sell_selected();
+        assert_eq!(state.sell_selected(), None);
+        assert_eq!(state.upgrades[0].owned, 0);
+        assert_eq!(state.upgrades[0].sell(), 0.0);
+    }
+
+    #[test]
+    fn undo_refunds_the_last_buy_until_gold_moves() {
+        let clock = MockClock::new();
+        let mut state = GameState::with_clock(Box::new(clock.clone()));
+        state.gold = 1000.0;
+        state.buy_all_affordable();
+        assert!(state.gold < 1000.0);
+
+        assert!(state.undo_purchase().unwrap() > 0);
+        assert!((state.gold - 1000.0).abs() < 1e-9);
+        assert!(state.total_gold_spent.abs() < 1e-9);
+        assert_eq!(state.total_upgrades_purchased, 0);
+        assert!(state.upgrades.iter().all(|u| u.owned == 0 && u.max_owned == 0));
+        assert!(state.undo_purchase().is_none());
+
+        // A click in between, or waiting too long, locks the purchase in
+        state.buy_selected();
+        state.click_for_gold();
+        assert!(state.undo_purchase().is_none());
+        clock.advance(Duration::from_secs(1));
+        state.buy_selected();
+        clock.advance(UNDO_WINDOW + Duration::from_millis(1));
+        assert!(state.undo_purchase().is_none());
+    }
+
+    #[test]
+    fn first_affordable_follows_the_displayed_order() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.upgrades[0].owned = 10;
+        state.gold = 60.0;
+        assert!(state.select_first_affordable());
+        assert_eq!(state.get_current_upgrades()[state.selected_upgrade].name, "Pickaxe");
+
+        // Ten pickaxes make the next one poor value, so the Shovel sorts first
+        state.sort_mode = SortMode::Roi;
+        assert!(state.select_first_affordable());
+        assert_eq!(state.get_current_upgrades()[state.selected_upgrade].name, "Shovel");
+
+        state.filter = "drill".to_string();
+        state.selected_upgrade = 0;
+        assert!(!state.select_first_affordable());
+        assert_eq!(state.selected_upgrade, 0);
+    }
+
+    #[test]
+    fn start_tab_opens_the_chosen_tab() {
+        let options = cli::Options { start_tab: Tab::by_name("Stats"), ..cli::Options::default() };
+        let app = test_app("start-tab", &options);
+        assert!(app.game_state.current_tab == Tab::Stats);
+        assert!(Tab::by_name("inventory").is_none());
+    }
+
+    #[test]
+    fn crossing_a_milestone_celebrates_once() {
+        let mut app = test_app("celebration", &cli::Options::default());
+        app.game_state.total_clicks = 999;
+        app.on_tick();
+        assert!(app.celebration.is_none());
+
+        app.game_state.total_clicks = 1000;
+        app.game_state.total_gold_earned = 2e6;
+        app.on_tick();
+        app.on_tick();
+        assert_eq!(app.celebration.map(|(message, _)| message), Some("Your first million gold!"));
+        assert_eq!(app.celebration_queue, ["One thousand clicks!"]);
+        let screen = render(&mut app, 100, 30);
+        assert!(screen.contains("Your first million gold!"), "{}", screen);
+
+        // Loading a save that's already past them celebrates nothing
+        app.celebration_queue.clear();
+        app.replace_game_state(GameState { total_gold_earned: 5e6, total_clicks: 5000, ..GameState::default() });
+        app.on_tick();
+        assert!(app.celebration_queue.is_empty());
+    }
+
+    #[test]
+    fn dumb_terminals_and_pipes_are_refused() {
+        assert!(unsupported_terminal(Some("xterm-256color"), true, true).is_none());
+        assert!(unsupported_terminal(None, true, true).is_none());
+        assert!(unsupported_terminal(Some("dumb"), true, true).is_some());
+        assert!(unsupported_terminal(Some("xterm"), true, false).is_some());
+        assert!(unsupported_terminal(Some("xterm"), false, true).is_some());
+    }
+
+    #[test]
+    fn recommendation_weighs_clicking_against_passive_income() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.recompute_rates();
+        let name = state.select_recommended().unwrap();
+        assert!(state.current_tab == Tab::Passive);
+        assert_eq!(state.get_current_upgrades()[state.selected_upgrade].name, name);
+
+        // At a click a second, +1 gold per click for 25 gold beats every passive upgrade
+        state.click_cooldown = Duration::ZERO;
+        for _ in 0..10 {
+            state.click_for_gold();
+        }
+        state.filter = "pick".to_string();
+        assert_eq!(state.select_recommended().as_deref(), Some("Strong Arms"));
+        assert!(state.current_tab == Tab::Click);
+        assert!(state.filter.is_empty());
+        assert_eq!(state.get_current_upgrades()[state.selected_upgrade].name, "Strong Arms");
+    }
+
+    #[test]
+    fn corrupt_saves_are_backed_up_and_replaced() {
+        let dir = test_dir("corrupt-open");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let truncated = format!("{{\"version\": {}, \"go", save::SAVE_VERSION);
+        std::fs::write(dir.join("save.json"), &truncated).unwrap();
+
+        let mut app = test_app("corrupt-open", &cli::Options::default());
+        assert!(app.save_blocked.is_none());
+        assert_eq!(app.game_state.gold, 0.0);
+        assert_eq!(app.notices.len(), 1);
+        assert_eq!(std::fs::read_to_string(dir.join("save.json.bak")).unwrap(), truncated);
+
+        app.save().unwrap();
+        assert!(save::load_game(&dir.join("save.json")).is_ok());
+    }
+
+    #[test]
+    fn start_gold_only_applies_to_new_games() {
+        let dir = test_dir("start-gold");
+        let _ = std::fs::remove_dir_all(&dir);
+        let options = cli::Options { start_gold: Some(500.0), ..cli::Options::default() };
+        let mut app = test_app("start-gold", &options);
+        assert_eq!(app.game_state.gold, 500.0);
+
+        app.game_state.gold = 20.0;
+        app.save().unwrap();
+        app.open_save(dir.join("save.json"));
+        assert_eq!(app.game_state.gold, 20.0);
+        assert_eq!(app.active_toast(), Some("--start-gold only applies to new games"));
+
+        app.reset_game();
+        assert_eq!(app.game_state.gold, 500.0);
+    }
+
+    #[test]
+    fn bound_keys_win_over_debug_cheats() {
+        let dir = test_dir("debug-keys");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("keybinds.toml"), "pause = \"g\"\n").unwrap();
+        let mut app = test_app("debug-keys", &cli::Options { debug: true, ..cli::Options::default() });
+        app.keymap = Keymap::load(&dir.join("keybinds.toml")).0;
+
+        app.on_key(KeyCode::Char('g'));
+        assert!(app.game_state.paused);
+        assert_eq!(app.game_state.gold, 0.0);
+        app.game_state.gold = 1.0;
+        app.on_key(KeyCode::Char('G'));
+        assert_eq!(app.game_state.gold, 10.0);
+    }
+
+    #[test]
+    fn migrate_v9_to_v10_starts_an_empty_history() {
+        let dir = test_dir("history-migration");
+        let _ = std::fs::remove_dir_all(&dir);
+        let state = GameState { prestige_count: 3, ..GameState::default() };
+        save::save_game(&state, &dir.join("save.json")).unwrap();
+        let mut old: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join("save.json")).unwrap()).unwrap();
+        old["version"] = serde_json::json!(9);
+        old.as_object_mut().unwrap().remove("prestige_history");
+        std::fs::write(dir.join("save.json"), old.to_string()).unwrap();
+
+        let mut app = test_app("history-migration", &cli::Options::default());
+        assert_eq!(app.game_state.prestige_count, 3);
+        assert!(app.game_state.prestige_history.is_empty());
+        app.overlay = Some(Overlay::Document { document: Document::PrestigeHistory, scroll: 0 });
+        assert!(render(&mut app, 100, 40).contains("No prestiges yet"));
+    }
+
+    #[test]
+    fn wrapping_selection_cycles_past_the_ends() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        let last = state.selection_len() - 1;
+        state.select_previous();
+        assert_eq!(state.selected_upgrade, 0);
+
+        state.wrap_selection = true;
+        state.select_previous();
+        assert_eq!(state.selected_upgrade, last);
+        state.select_next();
+        assert_eq!(state.selected_upgrade, 0);
+    }
+
+    #[test]
+    fn enter_acts_on_the_focused_pane() {
+        let mut app = test_app("focus", &cli::Options::default());
+        app.game_state.crit_chance = 0.0;
+        app.game_state.gold = 10.0;
+        assert_eq!(app.focus, Focus::List);
+        let screen = render(&mut app, 120, 40);
+        assert!(screen.contains("\u{2554}"), "{}", screen);
+
+        // List: Enter buys
+        app.on_key(KeyCode::Enter);
+        assert_eq!(app.game_state.upgrades[0].owned, 1);
+
+        // Mining: Enter mines instead
+        app.on_key(KeyCode::BackTab);
+        assert_eq!(app.focus, Focus::Mining);
+        app.on_key(KeyCode::Enter);
+        assert_eq!(app.game_state.total_clicks, 1);
+        assert_eq!(app.game_state.upgrades[0].owned, 1);
+
+        // Tab bar: Left/Right switch tabs, wrapping, and Enter drops back into the list
+        app.on_key(KeyCode::BackTab);
+        assert_eq!(app.focus, Focus::Tabs);
+        app.on_key(KeyCode::Left);
+        assert!(app.game_state.current_tab == Tab::Prestige);
+        app.on_key(KeyCode::Right);
+        app.on_key(KeyCode::Right);
+        assert!(app.game_state.current_tab == Tab::Click);
+        app.on_key(KeyCode::Enter);
+        assert_eq!(app.focus, Focus::List);
+        app.on_key(KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Tabs);
+    }
+
+    #[test]
+    fn tabs_remember_their_selection() {
+        let mut state = GameState::default();
+        state.select_down_by(3);
+        state.switch_tab(Tab::Click);
+        assert_eq!(state.selected_upgrade, 0);
+        state.select_next();
+
+        state.switch_tab(Tab::Passive);
+        assert_eq!(state.selected_upgrade, 3);
+        state.switch_tab(Tab::Click);
+        assert_eq!(state.selected_upgrade, 1);
+
+        // Coming back to a list that shrank lands on its last row
+        state.switch_tab(Tab::Passive);
+        state.select_down_by(100);
+        state.switch_tab(Tab::Click);
+        state.filter = "pickaxe".to_string();
+        state.switch_tab(Tab::Passive);
+        assert_eq!(state.selected_upgrade, 0);
+    }
+
+    #[test]
+    fn hiding_completed_achievements_shrinks_the_list() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.switch_tab(Tab::Achievements);
+        let total = state.achievements.len();
+        state.achievements[0].completed = true;
+        state.achievements[total - 1].completed = true;
+        state.selected_upgrade = total - 1;
+
+        state.hide_completed = true;
+        state.clamp_selection();
+        assert_eq!(state.selection_len(), total - 2);
+        assert_eq!(state.selected_upgrade, total - 3);
+        assert!(state.visible_achievements().iter().all(|a| !a.completed));
+    }
+
+    #[test]
+    fn tutorial_runs_once_for_new_players() {
+        let save_path = test_dir("tutorial").join("save.json");
+        let _ = std::fs::remove_file(&save_path);
+        // test_app skips the tutorial; opening the still-missing save again brings it back
+        let mut app = test_app("tutorial", &cli::Options::default());
+        app.open_save(save_path.clone());
+        assert_eq!(app.tutorial_step, Some(0));
+        assert!(render(&mut app, 120, 40).contains("Tutorial 1/3"));
+
+        // Other keys still play while it's up
+        app.on_key(KeyCode::Char(' '));
+        assert_eq!(app.game_state.total_clicks, 1);
+        app.on_key(KeyCode::Enter);
+        assert_eq!(app.tutorial_step, Some(1));
+        app.on_key(KeyCode::Esc);
+        assert_eq!(app.tutorial_step, None);
+        assert!(app.game_state.tutorial_seen);
+
+        app.save().unwrap();
+        app.open_save(save_path.clone());
+        assert_eq!(app.tutorial_step, None);
+        let _ = std::fs::remove_file(&save_path);
+    }
+
+    #[test]
+    fn locked_upgrades_cant_be_bought() {
+        let mut state = GameState { gold: 1e6, ..GameState::default() };
+        let drill = state.upgrades.iter().position(|u| u.name == "Drill").unwrap();
+        assert!(!state.is_unlocked(&state.upgrades[drill]));
+
+        // Selecting the locked row directly still doesn't buy it
+        state.selected_upgrade = state.get_current_upgrades().iter().position(|u| u.name == "Drill").unwrap();
+        assert!(!state.buy_selected());
+        assert_eq!(state.buy_to_milestone(), 0);
+        assert_eq!(state.upgrades[drill].owned, 0);
+
+        let shovel = state.upgrades.iter().position(|u| u.name == "Shovel").unwrap();
+        state.upgrades[shovel].owned = 5;
+        assert!(state.buy_selected());
+        assert_eq!(state.upgrades[drill].owned, 1);
+    }
+
+    #[test]
+    fn active_income_follows_recent_clicks() {
+        let clock = MockClock::new();
+        let mut state = GameState::with_clock(Box::new(clock.clone()));
+        state.crit_chance = 0.0;
+        state.click_cooldown = Duration::ZERO;
+        assert_eq!(state.active_income(), 0.0);
+
+        for _ in 0..5 {
+            state.click_for_gold();
+            clock.advance(Duration::from_secs(1));
+        }
+        // Five 1-gold clicks in the last ten seconds
+        assert!((state.active_income() - 0.5).abs() < 1e-9);
+
+        clock.advance(CLICK_RATE_WINDOW);
+        assert_eq!(state.active_income(), 0.0);
+    }
+
+    #[test]
+    fn prestige_history_keeps_the_latest_runs() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        for run in 1..=PRESTIGE_HISTORY_CAPACITY + 2 {
+            state.total_gold_earned = run as f64 * 1e6;
+            state.prestige();
+        }
+        assert_eq!(state.prestige_history.len(), PRESTIGE_HISTORY_CAPACITY);
+        let latest = state.prestige_history.last().unwrap();
+        assert_eq!(latest.gold_earned, (PRESTIGE_HISTORY_CAPACITY + 2) as f64 * 1e6);
+        assert_eq!(latest.points, prestige::points_for(latest.gold_earned));
+        assert_eq!(state.prestige_history[0].gold_earned, 3e6);
+    }
+
+    #[test]
+    fn auto_buy_needs_mine_managers() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        assert_eq!(state.toggle_auto_buy(), Some(("Pickaxe".to_string(), true)));
+        state.gold = 100.0;
+        state.advance(0.1);
+        assert_eq!(state.upgrades[0].owned, 0);
+
+        state.prestige_upgrades.iter_mut().find(|u| u.effect == prestige::PrestigeEffect::AutoBuy).unwrap().owned = 1;
+        state.advance(0.1);
+        // 10 + 11.5 + 13.2 + 15.2 + 17.5 + 20.1 = 87.5 spent; the seventh copy's 23.1 is out of reach
+        assert_eq!(state.upgrades[0].owned, 6);
+        assert!(state.gold < state.upgrades[0].current_cost());
+        assert!(state.last_purchase.is_none());
+
+        // Auto-buying after a manual buy leaves nothing to undo
+        state.gold = 100.0;
+        state.buy_selected();
+        assert!(state.last_purchase.is_some());
+        state.advance(0.1);
+        assert!(state.undo_purchase().is_none());
+    }
+
+    #[test]
+    fn same_seed_rolls_the_same_game() {
+        let play = |seed| {
+            let mut state = new_game(Difficulty::Normal, Some(seed));
+            state.event_chance = 0.5;
+            state.click_cooldown = Duration::ZERO;
+            let start = state.clock.now();
+            let mut log = Vec::new();
+            for step in 1..=200 {
+                state.advance(0.1);
+                let click = state.click_at(start + Duration::from_millis(step * 100)).unwrap();
+                log.push((click.crit, state.active_events.len(), state.nugget.as_ref().map(|n| n.x.to_bits())));
+            }
+            log
+        };
+        assert_eq!(play(42), play(42));
+        assert_ne!(play(42), play(43));
+    }
+
+    #[test]
+    fn goal_tracks_progress_until_reached() {
+        assert_eq!(GameState::parse_amount("2.5k"), Some(2_500.0));
+        assert_eq!(GameState::parse_amount(" 1e6 "), Some(1e6));
+        assert_eq!(GameState::parse_amount("3M"), Some(3e6));
+        assert_eq!(GameState::parse_amount("lots"), None);
+        assert_eq!(GameState::parse_amount("-5"), None);
+
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.goal = Some(100.0);
+        state.gold = 40.0;
+        state.gold_per_second = 3.0;
+        assert_eq!(state.goal_eta(), Some(Duration::from_secs(20)));
+        assert_eq!(state.check_goal(), None);
+
+        state.gold = 100.0;
+        assert_eq!(state.check_goal(), Some(100.0));
+        assert_eq!(state.goal, None);
+    }
+
+    #[test]
+    fn preferences_outlast_a_reset_and_save_when_changed() {
+        let dir = test_dir("preferences");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut app = test_app("preferences", &cli::Options::default());
+        let preferences = Preferences { theme: "mono".to_string(), number_format: NumberFormat::Scientific, precision: 0, accessible: true };
+        app.use_preferences(preferences, Some(dir.join("preferences.json")));
+        assert_eq!(app.theme.name, "mono");
+        assert!(app.accessible);
+
+        app.reset_game();
+        assert_eq!(app.game_state.number_format, NumberFormat::Scientific);
+        assert_eq!(app.game_state.precision, 0);
+
+        app.on_key(KeyCode::Char('f'));
+        let (saved, warnings) = Preferences::load(&dir.join("preferences.json"));
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(saved.number_format, NumberFormat::Grouped);
+        assert_eq!(saved.theme, "mono");
+    }
+
+    #[test]
+    fn share_summary_has_a_fixed_layout() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.total_gold_earned = 1.23e9;
+        state.total_upgrades_purchased = 340;
+        state.achievements[0].completed = true;
+        state.play_time = 2.0 * 3600.0 + 13.0 * 60.0 + 59.0;
+        state.prestige_count = 3;
+        let total = state.achievements.len();
+        assert_eq!(state.share_summary(), format!("TGM: 1.2B gold, 340 upgrades, 1/{} achievements, 2h13m, prestige 3", total));
+
+        state.advance(0.5);
+        state.advance(0.5);
+        assert!((state.play_time - (2.0 * 3600.0 + 14.0 * 60.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drills_boost_pickaxes() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.upgrades[0].owned = 10;
+        state.recompute_rates();
+        let without = state.gold_per_second;
+
+        state.upgrades[2].owned = 5;
+        state.recompute_rates();
+        assert!((state.upgrades[0].synergy_bonus - 0.05).abs() < 1e-9);
+        // Ten pickaxes at +5% plus the drills' own output
+        assert!((state.gold_per_second - (without * 1.05 + 5.0 * 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn buy_to_milestone_stops_at_the_milestone() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.upgrades[0].owned = 18;
+        let cost = state.upgrades[0].bulk_cost(7);
+        state.gold = cost + 1.0;
+        assert_eq!(state.buy_to_milestone(), 7);
+        assert_eq!(state.upgrades[0].owned, 25);
+        assert!((state.gold - 1.0).abs() < 1e-6);
+
+        // Short of the full run it buys what it can
+        state.gold = state.upgrades[0].bulk_cost(3);
+        assert_eq!(state.buy_to_milestone(), 3);
+        assert_eq!(state.upgrades[0].owned, 28);
+    }
+
+    #[test]
+    fn streak_grows_on_consecutive_days_only() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        assert!(state.check_in(100).is_none());
+        assert_eq!(state.login_streak, 1);
+
+        // Same day again changes nothing
+        assert!(state.check_in(100).is_none());
+        assert_eq!(state.login_streak, 1);
+
+        let bonus = state.check_in(101).unwrap();
+        assert_eq!(bonus.streak, 2);
+        assert_eq!(bonus.gold, 2.0 * DAILY_BONUS_MIN_GOLD);
+        assert_eq!(state.gold, bonus.gold);
+
+        // Skipping a day starts over
+        assert!(state.check_in(103).is_none());
+        assert_eq!(state.login_streak, 1);
+    }
+
+    #[test]
+    fn milestones_double_production_every_25() {
+        assert!((pickaxe(24).current_production() - 2.4).abs() < 1e-9);
+        assert!((pickaxe(25).current_production() - 5.0).abs() < 1e-9);
+        assert!((pickaxe(50).current_production() - 20.0).abs() < 1e-9);
+        assert_eq!(pickaxe(24).next_milestone(), 25);
+        assert_eq!(pickaxe(25).next_milestone(), 50);
+    }
+
+    #[test]
+    fn format_number_short_boundaries() {
+        let fmt = |n| GameState::format_number(n, NumberFormat::Short, 2);
+        assert_eq!(fmt(0.0), "0.00");
+        assert_eq!(fmt(999.99), "999.99");
+        assert_eq!(fmt(1_000.0), "1.00K");
+        assert_eq!(fmt(999_990.0), "999.99K");
+        assert_eq!(fmt(999_999.0), "1.00M");
+        assert_eq!(fmt(1_000_000.0), "1.00M");
+        assert_eq!(fmt(1_500_000_000.0), "1.50B");
+        assert_eq!(fmt(2.5e12), "2.50T");
+        assert_eq!(fmt(1e15), "1.00aa");
+        assert_eq!(fmt(1e18), "1.00ab");
+        assert_eq!(fmt(1e15 * 1000f64.powi(26)), "1.00ba");
+    }
+
+    #[test]
+    fn format_number_whole_numbers() {
+        let fmt = |n| GameState::format_number(n, NumberFormat::Short, 0);
+        assert_eq!(fmt(999.4), "999");
+        assert_eq!(fmt(999.6), "1000");
+        assert_eq!(fmt(1_499.0), "1K");
+        assert_eq!(fmt(999_499.0), "999K");
+        assert_eq!(fmt(999_500.0), "1M");
+        assert_eq!(GameState::format_number(1_500_000.0, NumberFormat::Scientific, 0), "2e6");
+    }
+
+    #[test]
+    fn format_number_three_decimals() {
+        let fmt = |n| GameState::format_number(n, NumberFormat::Short, 3);
+        assert_eq!(fmt(1.0), "1.000");
+        assert_eq!(fmt(999_999.0), "999.999K");
+        assert_eq!(fmt(999_999.6), "1.000M");
+        assert_eq!(fmt(1_234_567.0), "1.235M");
+        assert_eq!(GameState::format_number(1_234_567.0, NumberFormat::Scientific, 3), "1.235e6");
+    }
+
+    #[test]
+    fn format_number_grouped_digits() {
+        let grouped = |n| GameState::format_number(n, NumberFormat::Grouped, 2);
+        let short = |n| GameState::format_number(n, NumberFormat::Short, 2);
+        assert_eq!(grouped(999.5), "999.50");
+        assert_eq!(short(999.5), "999.50");
+        assert_eq!(grouped(1_000.0), "1,000.00");
+        assert_eq!(short(1_000.0), "1.00K");
+        assert_eq!(grouped(1_234_567.891), "1,234,567.89");
+        assert_eq!(short(1_234_567.891), "1.23M");
+        assert_eq!(grouped(999_999.999), "1,000,000.00");
+        assert_eq!(grouped(-12_345.0), "-12,345.00");
+        assert_eq!(GameState::format_number(123_456.7, NumberFormat::Grouped, 0), "123,457");
+        assert_eq!(grouped(2.5e15), "2.50e15");
+    }
+
+    #[test]
+    fn gold_saturates_at_the_cap() {
+        let mut state = GameState::with_clock(Box::new(MockClock::new()));
+        state.crit_chance = 0.0;
+        state.click_cooldown = Duration::ZERO;
+        // 2^(4M / 25) overflows f64 many times over
+        state.upgrades[0].owned = 4_000_000;
+        state.recompute_rates();
+        assert_eq!(state.gold_per_second, GOLD_CAP);
+
+        for _ in 0..3 {
+            state.advance(1e6);
+            state.click_for_gold();
+        }
+        for value in [state.gold, state.total_gold_earned, state.click_power, state.upgrades[0].lifetime_gold] {
+            assert!(value.is_finite(), "{}", value);
+        }
+        assert_eq!(state.gold, GOLD_CAP);
+        assert_eq!(state.format(state.gold), "MAX");
+
+        let mut app = test_app("saturation", &cli::Options::default());
+        app.game_state = state;
+        let screen = render(&mut app, 120, 40);
+        assert!(screen.contains("MAX"));
+        for tab in [Tab::Passive, Tab::Stats, Tab::Prestige] {
+            app.switch_tab(tab);
+            let screen = render(&mut app, 120, 40);
+            assert!(!screen.contains("inf") && !screen.contains("NaN"), "{}", screen);
+        }
+    }
+
+    #[test]
+    fn format_number_scientific() {
+        let fmt = |n| GameState::format_number(n, NumberFormat::Scientific, 2);
+        assert_eq!(fmt(999.99), "999.99");
+        assert_eq!(fmt(1_000_000.0), "1.00e6");
+        assert_eq!(fmt(1_500_000_000.0), "1.50e9");
+    }
+
+    #[test]
+    fn bulk_cost_matches_summing_each_copy() {
+        for count in [1, 10, 100, 1000] {
+            let mut upgrade = pickaxe(3);
+            let closed_form = upgrade.bulk_cost(count);
+            let looped: f64 = (0..count).map(|_| upgrade.purchase()).sum();
+            assert!((closed_form - looped).abs() <= 1e-9 * looped, "{} copies: {} vs {}", count, closed_form, looped);
+        }
+    }
+
+    #[test]
+    fn a_million_copy_max_buy_is_instant() {
+        // Growth slow enough that a million copies stays finite
+        let upgrade = Upgrade::new("Pebble", "", 1.0, 1.00001, 0.1, UpgradeType::Passive);
+        let gold = upgrade.bulk_cost(1_000_000);
+
+        let started = Instant::now();
+        let count = upgrade.max_affordable(gold);
+        let cost = upgrade.bulk_cost(count);
+        let elapsed = started.elapsed();
+
+        assert_eq!(count, 1_000_000);
+        assert!(cost <= gold);
+        // A handful of float operations; a per-copy loop would take milliseconds even optimized.
+        // The bound is loose so a busy machine running unoptimized tests doesn't trip it
+        assert!(elapsed < Duration::from_millis(5), "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn buy_all_affordable_matches_buying_one_at_a_time() {
+        let mut bulk = GameState { gold: 1e9, ..GameState::default() };
+        let mut single = GameState { gold: 1e9, ..GameState::default() };
+
+        let bought = bulk.buy_all_affordable();
+        let mut expected = 0;
+        loop {
+            let cheapest = single.upgrades.iter()
+                .enumerate()
+                .filter(|(_, u)| u.upgrade_type == UpgradeType::Passive && single.is_unlocked(u))
+                .min_by(|(_, a), (_, b)| a.current_cost().total_cmp(&b.current_cost()))
+                .map(|(index, _)| index)
+                .unwrap();
+            if !single.upgrades[cheapest].can_afford(single.gold) {
+                break;
+            }
+            single.purchase(cheapest);
+            expected += 1;
+        }
+
+        assert_eq!(bought, expected);
+        assert_eq!(bulk.total_upgrades_purchased, expected);
+        for (a, b) in bulk.upgrades.iter().zip(&single.upgrades) {
+            assert_eq!(a.owned, b.owned, "{}", a.name);
+        }
+        assert!((bulk.gold - single.gold).abs() < 1e-3 * single.gold.max(1.0));
+    }
+
+    #[test]
+    fn prestige_resets_the_run_but_keeps_the_shop() {
+        let mut state = GameState { total_gold_earned: 4e6, gold: 123.0, ..GameState::default() };
+        state.upgrades[0].owned = 10;
+        state.prestige_upgrades[1].owned = 1;
+
+        assert_eq!(state.prestige(), 2);
+        assert_eq!(state.prestige_points, 2);
+        assert_eq!(state.upgrades[0].owned, 0);
+        assert_eq!(state.total_gold_earned, 0.0);
+        // One level of the starting gold item
+        assert_eq!(state.gold, 100.0);
+        assert_eq!(state.prestige_upgrades[1].owned, 1);
+
+        // Nothing earned this run, so nothing to gain
+        assert_eq!(state.prestige(), 0);
+        assert_eq!(state.prestige_count, 1);
+    }
+
+    #[test]
+    fn cave_in_halves_the_rate_and_shows_the_normal_one() {
+        let mut app = test_app("cave-in", &cli::Options::default());
+        app.game_state.upgrades[0].owned = 100;
+        app.game_state.recompute_rates();
+        let normal = app.game_state.gold_per_second;
+        assert_eq!(app.game_state.base_gold_per_second, normal);
+
+        app.game_state.active_events.push(ActiveEvent::new(EventKind::CaveIn, EventKind::CaveIn.duration()));
+        app.game_state.active_events.push(ActiveEvent::new(EventKind::GoldRush, EventKind::GoldRush.duration()));
+        app.game_state.recompute_rates();
+        // The rush doubles what the cave-in halved
+        assert!((app.game_state.gold_per_second - normal).abs() < 1e-9);
+        assert!((app.game_state.base_gold_per_second - normal * 2.0).abs() < 1e-9);
+
+        let screen = render(&mut app, 140, 40);
+        assert!(screen.contains(&format!("\u{2193} from {}", app.game_state.format(normal * 2.0))), "{}", screen);
+        assert!(screen.contains("Cave-in: -50% production"), "{}", screen);
+    }
+
+    #[test]
+    fn long_stall_pays_passive_gold_without_stretching_events() {
+        let clock = MockClock::new();
+        let mut state = GameState::with_clock(Box::new(clock.clone()));
+        state.upgrades[0].owned = 10; // 1 gold/sec
+        state.active_events.push(ActiveEvent::new(EventKind::GoldRush, EventKind::GoldRush.duration()));
+
+        clock.advance(Duration::from_secs(3600));
+        state.update();
+
+        // One boosted second at 2x, then the other 3599 at the base rate
+        assert!((state.gold - 3601.0).abs() < 1e-6, "{}", state.gold);
+        let rush = state.active_events.iter().find(|e| e.kind == EventKind::GoldRush).unwrap();
+        assert!((rush.remaining - (EventKind::GoldRush.duration() - 1.0)).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,21 @@
+use std::{env, path::PathBuf};
+
+const APP_DIR: &str = "tui-idle-game";
+
+/// Per-user data directory for the game (saves), if the platform tells us one.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .map(|dir| dir.join(APP_DIR))
+}
+
+/// Per-user config directory for the game (keybindings and other hand-edited files).
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join(APP_DIR))
+}
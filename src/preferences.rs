@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+use crate::{theme::Theme, NumberFormat, DEFAULT_PRECISION};
+
+/// Display settings that belong to the player rather than to a save, so every profile
+/// and every fresh game looks the same. Fields missing from the file keep their defaults.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: String,
+    pub number_format: NumberFormat,
+    // Decimal places, 0 to 4; --precision still wins for a single run
+    pub precision: usize,
+    // Same as always passing --accessible
+    pub accessible: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default().name.to_string(),
+            number_format: NumberFormat::Short,
+            precision: DEFAULT_PRECISION,
+            accessible: false,
+        }
+    }
+}
+
+impl Preferences {
+    /// Reads `path`, where a missing file just means the defaults. Anything unusable comes
+    /// back as a warning and falls back to the default for that setting.
+    pub fn load(path: &Path) -> (Self, Vec<String>) {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return (Self::default(), Vec::new()),
+            Err(err) => return (Self::default(), vec![format!("couldn't read {}: {}", path.display(), err)]),
+        };
+        Self::parse(&text)
+    }
+
+    fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut preferences: Preferences = match serde_json::from_str(text) {
+            Ok(preferences) => preferences,
+            Err(err) => return (Self::default(), vec![format!("invalid preferences: {}", err)]),
+        };
+        let mut warnings = Vec::new();
+        if Theme::by_name(&preferences.theme).is_none() {
+            warnings.push(format!("unknown theme '{}'", preferences.theme));
+            preferences.theme = Self::default().theme;
+        }
+        if preferences.precision > 4 {
+            warnings.push(format!("precision {} is out of range (expected 0 to 4)", preferences.precision));
+            preferences.precision = DEFAULT_PRECISION;
+        }
+        (preferences, warnings)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_keep_their_defaults() {
+        let (preferences, warnings) = Preferences::parse(r#"{"number_format": "scientific"}"#);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(preferences, Preferences { number_format: NumberFormat::Scientific, ..Preferences::default() });
+    }
+
+    #[test]
+    fn bad_values_warn_and_fall_back() {
+        let (preferences, warnings) = Preferences::parse(r#"{"theme": "neon", "precision": 9, "accessible": true}"#);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert_eq!(preferences, Preferences { accessible: true, ..Preferences::default() });
+
+        let (preferences, warnings) = Preferences::parse("not json");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(preferences, Preferences::default());
+    }
+}
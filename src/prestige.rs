@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Gold earned in a run for the first prestige point; points grow with the square root after that
+const GOLD_PER_POINT: f64 = 1_000_000.0;
+// Production and click bonus from each unspent prestige point
+const POINT_BONUS: f64 = 0.02;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PrestigeEffect {
+    // +25% click power per level
+    ClickPower,
+    // Start each run with 10x more gold per level (100, 1K, 10K, ...)
+    StartingGold,
+    // +2 hours of offline earnings per level
+    OfflineCap,
+    // Each level shaves 5% off how fast upgrade prices grow
+    CostScaling,
+    // Lets upgrades be set to buy themselves
+    AutoBuy,
+}
+
+/// Permanent upgrade bought with prestige points; survives every prestige.
+#[derive(Clone)]
+pub struct PrestigeUpgrade {
+    pub name: String,
+    pub description: String,
+    // Price of the first level in points; each further level costs this much more
+    pub base_cost: u64,
+    pub max_level: Option<u32>,
+    pub owned: u32,
+    pub effect: PrestigeEffect,
+}
+
+impl PrestigeUpgrade {
+    fn new(name: &str, description: &str, base_cost: u64, max_level: Option<u32>, effect: PrestigeEffect) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            base_cost,
+            max_level,
+            owned: 0,
+            effect,
+        }
+    }
+
+    pub fn current_cost(&self) -> u64 {
+        self.base_cost * (self.owned as u64 + 1)
+    }
+
+    pub fn is_maxed(&self) -> bool {
+        self.max_level.is_some_and(|max| self.owned >= max)
+    }
+
+    pub fn can_afford(&self, points: u64) -> bool {
+        !self.is_maxed() && points >= self.current_cost()
+    }
+}
+
+/// One past prestige, for the history log.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PrestigeRecord {
+    // Unix timestamp (seconds) of the prestige
+    pub at: u64,
+    // Gold the run earned before resetting
+    pub gold_earned: f64,
+    pub points: u64,
+}
+
+pub fn shop() -> Vec<PrestigeUpgrade> {
+    vec![
+        PrestigeUpgrade::new("Golden Touch", "+25% click power per level", 1, None, PrestigeEffect::ClickPower),
+        PrestigeUpgrade::new("Trust Fund", "Start each run with 100 gold, x10 per level", 2, Some(6), PrestigeEffect::StartingGold),
+        PrestigeUpgrade::new("Night Shift", "+2h offline earnings cap per level", 2, Some(8), PrestigeEffect::OfflineCap),
+        PrestigeUpgrade::new("Bulk Contracts", "Upgrade prices grow 5% slower per level", 3, Some(10), PrestigeEffect::CostScaling),
+        PrestigeUpgrade::new("Mine Managers", "Press Z on an upgrade to have it bought automatically", 5, Some(1), PrestigeEffect::AutoBuy),
+    ]
+}
+
+/// Points a run that earned `total_gold_earned` is worth.
+pub fn points_for(total_gold_earned: f64) -> u64 {
+    (total_gold_earned / GOLD_PER_POINT).sqrt().floor() as u64
+}
+
+/// Production and click multiplier from holding `points` unspent prestige points.
+pub fn points_multiplier(points: u64) -> f64 {
+    1.0 + POINT_BONUS * points as f64
+}
+
+pub fn level(upgrades: &[PrestigeUpgrade], effect: PrestigeEffect) -> u32 {
+    upgrades.iter().filter(|u| u.effect == effect).map(|u| u.owned).sum()
+}
+
+pub fn click_multiplier(upgrades: &[PrestigeUpgrade]) -> f64 {
+    1.0 + 0.25 * level(upgrades, PrestigeEffect::ClickPower) as f64
+}
+
+pub fn starting_gold(upgrades: &[PrestigeUpgrade]) -> f64 {
+    match level(upgrades, PrestigeEffect::StartingGold) {
+        0 => 0.0,
+        level => 10f64.powi(level as i32 + 1),
+    }
+}
+
+pub fn offline_cap_bonus(upgrades: &[PrestigeUpgrade]) -> Duration {
+    Duration::from_secs(2 * 60 * 60) * level(upgrades, PrestigeEffect::OfflineCap)
+}
+
+pub fn auto_buy_unlocked(upgrades: &[PrestigeUpgrade]) -> bool {
+    level(upgrades, PrestigeEffect::AutoBuy) > 0
+}
+
+// Fraction of each upgrade's price growth that's waived
+pub fn cost_discount(upgrades: &[PrestigeUpgrade]) -> f64 {
+    (0.05 * level(upgrades, PrestigeEffect::CostScaling) as f64).min(0.5)
+}
@@ -0,0 +1,740 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{clock, paths, prestige::PrestigeRecord, rng::Rng, ActiveEvent, Difficulty, GameState, Upgrade, PRESTIGE_HISTORY_CAPACITY};
+
+// Overrides the save location when --save-path isn't given
+const SAVE_PATH_ENV: &str = "TUI_IDLE_SAVE";
+// Bump this and add a step to `migrate` whenever SaveData changes shape
+pub const SAVE_VERSION: u32 = 14;
+// Version tag at the front of exported save strings
+const EXPORT_PREFIX: &str = "TGM1:";
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Serialize, Deserialize)]
+struct SavedUpgrade {
+    name: String,
+    owned: u64,
+    // Added in v7; only tracked for regular upgrades, so prestige shop entries leave them out
+    #[serde(default)]
+    lifetime_gold: f64,
+    #[serde(default)]
+    max_owned: u64,
+    // Added in v11
+    auto_buy: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveData {
+    version: u32,
+    gold: f64,
+    total_gold_earned: f64,
+    total_clicks: u64,
+    total_upgrades_purchased: u64,
+    upgrades: Vec<SavedUpgrade>,
+    completed_achievements: Vec<String>,
+    // Unix timestamp (seconds) of when the save was written
+    saved_at: u64,
+    // Added in v2
+    active_events: Vec<ActiveEvent>,
+    // Added in v3
+    total_gold_spent: f64,
+    // Added in v4
+    prestige_points: u64,
+    prestige_count: u64,
+    prestige_upgrades: Vec<SavedUpgrade>,
+    // Added in v5
+    difficulty: Difficulty,
+    // Added in v6; a game saved while paused earns nothing offline
+    paused: bool,
+    // Added in v8
+    login_streak: u32,
+    last_play_day: Option<i64>,
+    // Added in v9
+    tutorial_seen: bool,
+    // Added in v10
+    prestige_history: Vec<PrestigeRecord>,
+    // Added in v12; older saves get a fresh seed on load
+    seed: Option<u64>,
+    rng_state: Option<u64>,
+    // Added in v13
+    goal: Option<f64>,
+    // Added in v14
+    play_time: f64,
+}
+
+impl SaveData {
+    fn from_state(state: &GameState) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            gold: state.gold,
+            total_gold_earned: state.total_gold_earned,
+            total_clicks: state.total_clicks,
+            total_upgrades_purchased: state.total_upgrades_purchased,
+            upgrades: state.upgrades.iter()
+                .map(|u| SavedUpgrade {
+                    name: u.name.clone(),
+                    owned: u.owned,
+                    lifetime_gold: u.lifetime_gold,
+                    max_owned: u.max_owned,
+                    auto_buy: u.auto_buy,
+                })
+                .collect(),
+            completed_achievements: state.achievements.iter()
+                .filter(|a| a.completed)
+                .map(|a| a.name.clone())
+                .collect(),
+            saved_at: state.clock.unix_time(),
+            active_events: state.active_events.clone(),
+            total_gold_spent: state.total_gold_spent,
+            prestige_points: state.prestige_points,
+            prestige_count: state.prestige_count,
+            prestige_upgrades: state.prestige_upgrades.iter()
+                .map(|u| SavedUpgrade { name: u.name.clone(), owned: u.owned as u64, lifetime_gold: 0.0, max_owned: 0, auto_buy: false })
+                .collect(),
+            difficulty: state.difficulty,
+            paused: state.paused,
+            login_streak: state.login_streak,
+            last_play_day: state.last_play_day,
+            tutorial_seen: state.tutorial_seen,
+            prestige_history: state.prestige_history.clone(),
+            seed: Some(state.seed),
+            rng_state: Some(state.rng.state()),
+            goal: state.goal,
+            play_time: state.play_time,
+        }
+    }
+
+    fn into_state(self) -> GameState {
+        // An edited or imported save can list more runs than it prestiged, or more than we keep
+        let mut prestige_history = self.prestige_history;
+        let keep = (self.prestige_count as usize).min(PRESTIGE_HISTORY_CAPACITY);
+        prestige_history.drain(..prestige_history.len().saturating_sub(keep));
+
+        let mut state = GameState {
+            gold: self.gold,
+            total_gold_earned: self.total_gold_earned,
+            total_gold_spent: self.total_gold_spent,
+            total_clicks: self.total_clicks,
+            total_upgrades_purchased: self.total_upgrades_purchased,
+            active_events: self.active_events,
+            // v1 saves could carry a zero timestamp, which means "unknown"
+            saved_at: Some(self.saved_at).filter(|&t| t > 0),
+            prestige_points: self.prestige_points,
+            prestige_count: self.prestige_count,
+            paused: self.paused,
+            login_streak: self.login_streak,
+            last_play_day: self.last_play_day,
+            tutorial_seen: self.tutorial_seen,
+            prestige_history,
+            goal: self.goal,
+            play_time: self.play_time,
+            ..GameState::default()
+        };
+        state.set_difficulty(self.difficulty);
+        // Carry on the same sequence of rolls, so a seeded game plays out the same
+        if let (Some(seed), Some(rng_state)) = (self.seed, self.rng_state) {
+            state.seed = seed;
+            state.rng = Rng::from_state(rng_state);
+        }
+
+        // Match by name so reordering or adding upgrades doesn't scramble old saves
+        for saved in self.upgrades {
+            if let Some(upgrade) = state.upgrades.iter_mut().find(|u| u.name == saved.name) {
+                upgrade.owned = saved.owned;
+                upgrade.lifetime_gold = saved.lifetime_gold;
+                upgrade.max_owned = saved.max_owned.max(saved.owned);
+                upgrade.auto_buy = saved.auto_buy;
+            }
+        }
+        for saved in self.prestige_upgrades {
+            if let Some(upgrade) = state.prestige_upgrades.iter_mut().find(|u| u.name == saved.name) {
+                upgrade.owned = saved.owned as u32;
+            }
+        }
+        for achievement in &mut state.achievements {
+            achievement.completed = self.completed_achievements.contains(&achievement.name);
+        }
+
+        state
+    }
+}
+
+/// Location of the save file inside the platform's per-user data directory.
+pub fn default_save_path() -> PathBuf {
+    match paths::data_dir() {
+        Some(dir) => dir.join("save.json"),
+        None => PathBuf::from("save.json"),
+    }
+}
+
+/// Save location picked on the command line or environment: `--save-path`, then
+/// `--profile`, then `$TUI_IDLE_SAVE`. None means the default (or a profile chosen in game).
+pub fn explicit_save_path(flag: Option<&Path>, profile: Option<&str>) -> Option<PathBuf> {
+    flag.map(Path::to_path_buf)
+        .or_else(|| profile.map(profile_save_path))
+        .or_else(|| env::var_os(SAVE_PATH_ENV).filter(|v| !v.is_empty()).map(PathBuf::from))
+}
+
+/// Save file for a named profile, next to the default save.
+pub fn profile_save_path(name: &str) -> PathBuf {
+    default_save_path().with_file_name(format!("save-{}.json", name))
+}
+
+/// Names of the profiles that have a save in the data directory, sorted.
+pub fn list_profiles() -> Vec<String> {
+    let Some(dir) = default_save_path().parent().map(Path::to_path_buf) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|file| Some(file.strip_prefix("save-")?.strip_suffix(".json")?.to_string()))
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Creates the save's parent directories and makes sure the file can be written,
+/// so a bad path is reported at startup rather than when the first save fails.
+pub fn ensure_writable(path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    if path.exists() {
+        // Appending nothing leaves an existing save untouched
+        OpenOptions::new().append(true).open(path)?;
+    } else {
+        // An empty file would fail to load, so don't leave the probe behind
+        OpenOptions::new().write(true).create_new(true).open(path)?;
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+pub fn save_game(state: &GameState, path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&SaveData::from_state(state))?;
+    write_atomically(path, &json)
+}
+
+// Writes a temporary file beside `path` and renames it over, so a crash or full disk
+// mid-write leaves the previous save whole instead of a truncated one
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let temp = with_suffix(path, ".tmp");
+    let result = File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+pub fn load_game(path: &Path) -> io::Result<GameState> {
+    let json = fs::read(path)?;
+    parse_save(&json)
+}
+
+/// Whether an error from `load_game` means the file itself is damaged: cut short, not
+/// JSON, or missing what a save needs. Unreadable files and saves from newer versions of
+/// the game aren't corrupt, and shouldn't be replaced.
+pub fn is_corrupt(err: &io::Error) -> bool {
+    err.get_ref().is_some_and(|inner| inner.is::<serde_json::Error>())
+}
+
+/// Moves a corrupt save to `<path>.bak` (replacing any older backup) so a new game can
+/// take its place without losing it, and returns where it went.
+pub fn back_up_corrupt_save(path: &Path) -> io::Result<PathBuf> {
+    let backup = with_suffix(path, ".bak");
+    fs::rename(path, &backup)?;
+    Ok(backup)
+}
+
+fn parse_save(json: &[u8]) -> io::Result<GameState> {
+    let save: Value = serde_json::from_slice(json)?;
+    // v1 saves predate the version field
+    let version = save.get("version").and_then(Value::as_u64).unwrap_or(1) as u32;
+    if version > SAVE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("save is from a newer version of the game (format v{}, this build understands up to v{})", version, SAVE_VERSION),
+        ));
+    }
+
+    let data: SaveData = serde_json::from_value(migrate(save, version))?;
+    Ok(data.into_state())
+}
+
+/// Upgrades a raw save from `from_version` to `SAVE_VERSION`, one format step at a time.
+fn migrate(mut save: Value, from_version: u32) -> Value {
+    if from_version < 2 {
+        // v2 started persisting running events; v1 games had none in flight
+        save["active_events"] = json!([]);
+    }
+    if from_version < 3 {
+        // Spending wasn't tracked yet; the price of what's owned is the closest estimate
+        let defaults = GameState::default();
+        let spent: f64 = save["upgrades"].as_array().into_iter().flatten()
+            .filter_map(|saved| {
+                let name = saved["name"].as_str()?;
+                let owned = saved["owned"].as_u64()?;
+                let upgrade = defaults.upgrades.iter().find(|u| u.name == name)?;
+                Some(Upgrade { owned, ..upgrade.clone() }.rebuy_value())
+            })
+            .sum();
+        save["total_gold_spent"] = json!(spent);
+    }
+    if from_version < 4 {
+        save["prestige_points"] = json!(0);
+        save["prestige_count"] = json!(0);
+        save["prestige_upgrades"] = json!([]);
+    }
+    if from_version < 5 {
+        // Difficulty levels came later; everything before played the normal balance
+        save["difficulty"] = json!("normal");
+    }
+    if from_version < 6 {
+        save["paused"] = json!(false);
+    }
+    if from_version < 7 {
+        // Production wasn't tracked per upgrade; what's owned now is the best known peak
+        for saved in save["upgrades"].as_array_mut().into_iter().flatten() {
+            saved["lifetime_gold"] = json!(0.0);
+            saved["max_owned"] = saved["owned"].clone();
+        }
+    }
+    if from_version < 8 {
+        // Count the day of the last save as played, so coming back tomorrow already counts
+        let saved_at = save["saved_at"].as_u64().filter(|&t| t > 0);
+        save["login_streak"] = json!(if saved_at.is_some() { 1 } else { 0 });
+        save["last_play_day"] = json!(saved_at.map(clock::local_day));
+    }
+    if from_version < 9 {
+        // Anyone with an older save has already found their way around
+        save["tutorial_seen"] = json!(true);
+    }
+    if from_version < 10 {
+        // Earlier prestiges weren't logged, only counted
+        save["prestige_history"] = json!([]);
+    }
+    if from_version < 11 {
+        for key in ["upgrades", "prestige_upgrades"] {
+            for saved in save[key].as_array_mut().into_iter().flatten() {
+                saved["auto_buy"] = json!(false);
+            }
+        }
+    }
+    if from_version < 12 {
+        save["seed"] = Value::Null;
+        save["rng_state"] = Value::Null;
+    }
+    if from_version < 13 {
+        save["goal"] = Value::Null;
+    }
+    if from_version < 14 {
+        // Time played wasn't tracked before
+        save["play_time"] = json!(0.0);
+    }
+    save["version"] = json!(SAVE_VERSION);
+    save
+}
+
+// Human/tool-readable summary written by --stats-json; never read back
+#[derive(Serialize)]
+struct SessionStats<'a> {
+    gold: f64,
+    total_gold_earned: f64,
+    total_clicks: u64,
+    total_upgrades_purchased: u64,
+    upgrades_owned: BTreeMap<&'a str, u64>,
+    achievements_completed: Vec<&'a str>,
+    play_time_seconds: f64,
+}
+
+/// Writes end-of-session stats as pretty JSON, for players who log their sessions.
+pub fn write_stats(state: &GameState, play_time: Duration, path: &Path) -> io::Result<()> {
+    let stats = SessionStats {
+        gold: state.gold,
+        total_gold_earned: state.total_gold_earned,
+        total_clicks: state.total_clicks,
+        total_upgrades_purchased: state.total_upgrades_purchased,
+        upgrades_owned: state.upgrades.iter().map(|u| (u.name.as_str(), u.owned)).collect(),
+        achievements_completed: state.achievements.iter()
+            .filter(|a| a.completed)
+            .map(|a| a.name.as_str())
+            .collect(),
+        play_time_seconds: play_time.as_secs_f64(),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&stats)?)
+}
+
+/// Removes the save file; a missing file counts as already deleted.
+pub fn delete_save(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Encodes the game as a copy-pasteable, version-tagged base64 string.
+pub fn encode_save(state: &GameState) -> String {
+    let json = serde_json::to_vec(&SaveData::from_state(state)).expect("save data always serializes");
+    format!("{}{}", EXPORT_PREFIX, base64_encode(&json))
+}
+
+/// Restores a game from a string produced by [`encode_save`].
+pub fn decode_save(encoded: &str) -> io::Result<GameState> {
+    let encoded = encoded.trim();
+    let payload = match encoded.strip_prefix(EXPORT_PREFIX) {
+        Some(payload) => payload,
+        None => {
+            let tag = encoded.split(':').next().unwrap_or_default();
+            let message = if encoded.contains(':') {
+                format!("unsupported save string version '{}'", tag)
+            } else {
+                "not a save string (missing version tag)".to_string()
+            };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+    };
+
+    let json = base64_decode(payload)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "save string is not valid base64"))?;
+    parse_save(&json)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        let value = BASE64_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = buffer << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn v1_save() -> Value {
+        json!({
+            "gold": 50.0,
+            "total_gold_earned": 1234.0,
+            "total_clicks": 10,
+            "total_upgrades_purchased": 3,
+            "upgrades": [{ "name": "Pickaxe", "owned": 3 }],
+            "completed_achievements": ["First Steps"],
+            "saved_at": 0,
+        })
+    }
+
+    #[test]
+    fn migrate_v1_to_v2_adds_version_and_events() {
+        let migrated = migrate(v1_save(), 1);
+        assert_eq!(migrated["version"], json!(SAVE_VERSION));
+        assert_eq!(migrated["active_events"], json!([]));
+        assert_eq!(migrated["gold"], json!(50.0));
+    }
+
+    #[test]
+    fn migrate_v2_to_v3_estimates_gold_spent() {
+        let mut save = v1_save();
+        save["version"] = json!(2);
+        save["active_events"] = json!([]);
+        let migrated = migrate(save, 2);
+        assert_eq!(migrated["version"], json!(SAVE_VERSION));
+        // Three pickaxes: 10 + 11.5 + 13.225
+        let spent = migrated["total_gold_spent"].as_f64().unwrap();
+        assert!((spent - 34.725).abs() < 1e-9, "{}", spent);
+    }
+
+    #[test]
+    fn migrate_v3_to_v4_starts_without_prestige() {
+        let migrated = migrate(migrate(v1_save(), 1), 3);
+        assert_eq!(migrated["prestige_points"], json!(0));
+        assert_eq!(migrated["prestige_upgrades"], json!([]));
+        let state = parse_save(migrated.to_string().as_bytes()).unwrap();
+        assert!(state.prestige_upgrades.iter().all(|u| u.owned == 0));
+    }
+
+    #[test]
+    fn difficulty_survives_a_round_trip() {
+        let mut state = GameState::default();
+        state.set_difficulty(Difficulty::Hard);
+        let json = serde_json::to_vec(&SaveData::from_state(&state)).unwrap();
+        let loaded = parse_save(&json).unwrap();
+        assert!(loaded.difficulty == Difficulty::Hard);
+        assert_eq!(loaded.upgrades[0].cost_multiplier, state.upgrades[0].cost_multiplier);
+        assert_eq!(loaded.upgrades[0].base_production, state.upgrades[0].base_production);
+    }
+
+    #[test]
+    fn paused_game_earns_nothing_while_closed() {
+        let clock = MockClock::new();
+        let mut state = GameState::with_clock(Box::new(clock.clone()));
+        state.upgrades[0].owned = 10;
+        state.recompute_rates();
+
+        let load_later = |state: &GameState| {
+            let json = serde_json::to_vec(&SaveData::from_state(state)).unwrap();
+            clock.advance(Duration::from_secs(3600));
+            let mut loaded = parse_save(&json).unwrap();
+            loaded.clock = Box::new(clock.clone());
+            loaded
+        };
+
+        state.paused = true;
+        let mut loaded = load_later(&state);
+        assert!(loaded.paused);
+        assert!(loaded.collect_offline_earnings(Duration::from_secs(8 * 3600)).is_none());
+        assert_eq!(loaded.gold, 0.0);
+
+        state.paused = false;
+        let mut loaded = load_later(&state);
+        let report = loaded.collect_offline_earnings(Duration::from_secs(8 * 3600)).unwrap();
+        assert_eq!(report.away, Duration::from_secs(3600));
+        assert!(loaded.gold > 0.0);
+    }
+
+    #[test]
+    fn migrations_fill_in_missing_fields() {
+        // (version the save was written at, field it didn't have yet, what migrating fills in)
+        let cases = [
+            (4, "difficulty", json!("normal")),
+            (5, "paused", json!(false)),
+            (11, "seed", Value::Null),
+            (11, "rng_state", Value::Null),
+            (12, "goal", Value::Null),
+            (13, "play_time", json!(0.0)),
+        ];
+        for (from_version, field, expected) in cases {
+            let mut save = migrate(v1_save(), 1);
+            save["version"] = json!(from_version);
+            save.as_object_mut().unwrap().remove(field);
+            let migrated = migrate(save, from_version);
+            // Indexing a missing key also gives Null, so look the field up
+            assert_eq!(migrated.get(field), Some(&expected), "v{} {}", from_version, field);
+            assert!(parse_save(migrated.to_string().as_bytes()).is_ok());
+        }
+    }
+
+    #[test]
+    fn migrate_v6_to_v7_starts_lifetime_stats() {
+        let migrated = migrate(migrate(v1_save(), 1), 6);
+        let pickaxe = &migrated["upgrades"][0];
+        assert_eq!(pickaxe["lifetime_gold"], json!(0.0));
+        assert_eq!(pickaxe["max_owned"], json!(3));
+    }
+
+    #[test]
+    fn saves_without_a_seed_roll_a_fresh_one() {
+        // With nothing to carry on, the load starts a fresh sequence from a new seed
+        let state = parse_save(v1_save().to_string().as_bytes()).unwrap();
+        assert_eq!(state.rng.state(), Rng::new(state.seed).state());
+    }
+
+    #[test]
+    fn lifetime_stats_survive_a_round_trip() {
+        let mut state = GameState::default();
+        state.upgrades[0].lifetime_gold = 123.5;
+        state.upgrades[0].max_owned = 40;
+        let json = serde_json::to_vec(&SaveData::from_state(&state)).unwrap();
+        let loaded = parse_save(&json).unwrap();
+        assert_eq!(loaded.upgrades[0].lifetime_gold, 123.5);
+        assert_eq!(loaded.upgrades[0].max_owned, 40);
+    }
+
+    #[test]
+    fn migrate_v7_to_v8_counts_the_last_save_day() {
+        let mut save = migrate(v1_save(), 1);
+        save["saved_at"] = json!(1_700_000_000u64);
+        let migrated = migrate(save, 7);
+        assert_eq!(migrated["login_streak"], json!(1));
+        assert_eq!(migrated["last_play_day"], json!(clock::local_day(1_700_000_000)));
+
+        // v1 saves have no saved_at, so there's no day to count
+        let state = parse_save(v1_save().to_string().as_bytes()).unwrap();
+        assert_eq!(state.login_streak, 0);
+        assert!(state.last_play_day.is_none());
+    }
+
+    #[test]
+    fn migrate_v8_to_v9_skips_the_tutorial() {
+        let state = parse_save(v1_save().to_string().as_bytes()).unwrap();
+        assert!(state.tutorial_seen);
+        assert!(!GameState::default().tutorial_seen);
+    }
+
+    #[test]
+    fn auto_buy_survives_a_round_trip() {
+        let mut state = GameState::default();
+        state.upgrades[1].auto_buy = true;
+        let loaded = parse_save(&serde_json::to_vec(&SaveData::from_state(&state)).unwrap()).unwrap();
+        assert!(!loaded.upgrades[0].auto_buy);
+        assert!(loaded.upgrades[1].auto_buy);
+    }
+
+    #[test]
+    fn migrate_v10_to_v11_turns_auto_buy_off() {
+        let mut save = migrate(v1_save(), 1);
+        save["version"] = json!(10);
+        save["prestige_upgrades"] = json!([{ "name": "Golden Touch", "owned": 1 }]);
+        save["upgrades"][0].as_object_mut().unwrap().remove("auto_buy");
+        assert!(serde_json::from_value::<SaveData>(save.clone()).is_err());
+
+        let migrated = migrate(save, 10);
+        assert_eq!(migrated["upgrades"][0]["auto_buy"], json!(false));
+        assert_eq!(migrated["prestige_upgrades"][0]["auto_buy"], json!(false));
+        assert!(parse_save(migrated.to_string().as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn loaded_games_continue_their_rolls() {
+        let mut state = crate::new_game(Difficulty::Normal, Some(7));
+        state.rng.next_u64();
+        let mut loaded = parse_save(&serde_json::to_vec(&SaveData::from_state(&state)).unwrap()).unwrap();
+        assert_eq!(loaded.seed, 7);
+        assert_eq!(loaded.rng.next_u64(), state.rng.next_u64());
+    }
+
+    #[test]
+    fn prestige_history_survives_a_round_trip() {
+        let mut state = GameState { total_gold_earned: 4e6, ..GameState::default() };
+        state.prestige();
+        let loaded = parse_save(&serde_json::to_vec(&SaveData::from_state(&state)).unwrap()).unwrap();
+        assert_eq!(loaded.prestige_history.len(), 1);
+        assert_eq!(loaded.prestige_history[0].points, 2);
+        assert_eq!(loaded.prestige_history[0].gold_earned, 4e6);
+
+        assert!(parse_save(v1_save().to_string().as_bytes()).unwrap().prestige_history.is_empty());
+    }
+
+    #[test]
+    fn prestige_history_is_trimmed_on_load() {
+        let record = |points: u64| json!({"at": 0, "gold_earned": 1e6, "points": points});
+        let mut save = migrate(v1_save(), 1);
+        save["version"] = json!(SAVE_VERSION);
+        save["prestige_count"] = json!(2);
+        save["prestige_history"] = json!([record(1), record(2), record(3)]);
+        let loaded = parse_save(save.to_string().as_bytes()).unwrap();
+        let points: Vec<u64> = loaded.prestige_history.iter().map(|r| r.points).collect();
+        assert_eq!(points, vec![2, 3]);
+
+        save["prestige_count"] = json!(1000);
+        save["prestige_history"] = Value::Array((0..PRESTIGE_HISTORY_CAPACITY as u64 + 5).map(record).collect());
+        let loaded = parse_save(save.to_string().as_bytes()).unwrap();
+        assert_eq!(loaded.prestige_history.len(), PRESTIGE_HISTORY_CAPACITY);
+        assert_eq!(loaded.prestige_history[0].points, 5);
+    }
+
+    #[test]
+    fn unversioned_save_loads_as_v1() {
+        let state = parse_save(v1_save().to_string().as_bytes()).unwrap();
+        assert_eq!(state.gold, 50.0);
+        assert_eq!(state.upgrades.iter().find(|u| u.name == "Pickaxe").unwrap().owned, 3);
+        assert!(state.achievements.iter().find(|a| a.name == "First Steps").unwrap().completed);
+        assert!(state.active_events.is_empty());
+    }
+
+    #[test]
+    fn current_save_round_trips() {
+        let state = GameState { gold: 42.0, ..GameState::default() };
+        let json = serde_json::to_vec(&SaveData::from_state(&state)).unwrap();
+        assert_eq!(parse_save(&json).unwrap().gold, 42.0);
+    }
+
+    #[test]
+    fn newer_save_is_refused() {
+        let mut save = v1_save();
+        save["version"] = json!(SAVE_VERSION + 1);
+        let err = parse_save(save.to_string().as_bytes()).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(!is_corrupt(&err));
+    }
+
+    #[test]
+    fn malformed_saves_are_corrupt() {
+        let truncated = format!("{{\"version\": {}, \"gold\": 12", SAVE_VERSION);
+        let empty = format!("{{\"version\": {}}}", SAVE_VERSION);
+        for json in ["", &truncated, "not a save", &empty] {
+            let err = parse_save(json.as_bytes()).err().unwrap();
+            assert!(is_corrupt(&err), "{:?}: {}", json, err);
+        }
+
+        let dir = env::temp_dir().join("tui-idle-game-corrupt-save-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("save.json");
+        fs::write(&path, "{\"gold\": 1").unwrap();
+        let err = load_game(&path).err().unwrap();
+        assert!(is_corrupt(&err));
+
+        let backup = back_up_corrupt_save(&path).unwrap();
+        assert_eq!(backup, dir.join("save.json.bak"));
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "{\"gold\": 1");
+    }
+
+    #[test]
+    fn saving_replaces_the_file_in_one_step() {
+        let dir = env::temp_dir().join("tui-idle-game-atomic-save-test");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("save.json");
+        save_game(&GameState { gold: 1.0, ..GameState::default() }, &path).unwrap();
+        save_game(&GameState { gold: 2.0, ..GameState::default() }, &path).unwrap();
+        assert_eq!(load_game(&path).unwrap().gold, 2.0);
+        assert!(!dir.join("save.json.tmp").exists());
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+use crate::{cli, new_game};
+
+// Synthetic time step, matching the interactive tick rate
+const STEP: f64 = 0.1;
+
+/// Plays `seconds` of game time without a terminal and prints a summary.
+///
+/// The strategy is fixed so runs are comparable while tuning balance: click
+/// whenever the cooldown allows, then buy the cheapest affordable upgrade
+/// until nothing else is affordable.
+pub fn run(seconds: f64, options: &cli::Options) {
+    let mut state = new_game(options.difficulty.unwrap_or_default(), options.seed);
+    if let Some(cooldown) = options.click_cooldown {
+        state.click_cooldown = cooldown;
+    }
+    if let Some(chance) = options.event_chance {
+        state.event_chance = chance;
+    }
+    state.event_duration = options.event_duration;
+    let start = Instant::now();
+    let mut elapsed = 0.0;
+    let mut unlocked = Vec::new();
+
+    while elapsed < seconds {
+        let step = STEP.min(seconds - elapsed);
+        elapsed += step;
+
+        unlocked.extend(state.advance(step));
+        state.click_at(start + Duration::from_secs_f64(elapsed));
+        while state.buy_cheapest() {}
+    }
+    state.recompute_rates();
+
+    println!("Simulated {:.0}s of play (seed {})", seconds, state.seed);
+    println!("  Gold:               {}", state.format(state.gold));
+    println!("  Total gold earned:  {}", state.format(state.total_gold_earned));
+    println!("  Gold per second:    {}", state.format(state.gold_per_second));
+    println!("  Gold per click:     {}", state.format(state.click_power));
+    println!("  Clicks:             {}", state.total_clicks);
+    println!("  Upgrades purchased: {}", state.total_upgrades_purchased);
+    println!("Upgrades owned:");
+    for upgrade in &state.upgrades {
+        println!("  {:<18} {}", upgrade.name, upgrade.owned);
+    }
+    println!("Achievements unlocked ({}/{}):", unlocked.len(), state.achievements.len());
+    for index in unlocked {
+        println!("  {}", state.achievements[index].name);
+    }
+}
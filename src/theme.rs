@@ -0,0 +1,110 @@
+use ratatui::style::{Color, Style};
+use std::env;
+
+/// Semantic colors used by the UI, so nothing in `ui` hardcodes a palette.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub name: &'static str,
+    pub gold: Color,
+    pub rate: Color,
+    pub click: Color,
+    pub total: Color,
+    pub affordable: Color,
+    pub unaffordable: Color,
+    pub heading: Color,
+    pub text: Color,
+    pub muted: Color,
+    pub event: Color,
+    pub highlight: Color,
+    pub tab: Color,
+    pub border: Color,
+    // False when the terminal shouldn't receive any color codes (NO_COLOR)
+    pub color: bool,
+}
+
+pub const CLASSIC: Theme = Theme {
+    name: "classic",
+    gold: Color::Yellow,
+    rate: Color::Green,
+    click: Color::Cyan,
+    total: Color::Magenta,
+    affordable: Color::Green,
+    unaffordable: Color::Red,
+    heading: Color::Cyan,
+    text: Color::White,
+    muted: Color::Gray,
+    event: Color::LightRed,
+    highlight: Color::DarkGray,
+    tab: Color::Blue,
+    border: Color::Reset,
+    color: true,
+};
+
+pub const MONO: Theme = Theme {
+    name: "mono",
+    gold: Color::White,
+    rate: Color::White,
+    click: Color::White,
+    total: Color::White,
+    affordable: Color::White,
+    unaffordable: Color::DarkGray,
+    heading: Color::White,
+    text: Color::White,
+    muted: Color::Gray,
+    event: Color::White,
+    highlight: Color::DarkGray,
+    tab: Color::DarkGray,
+    border: Color::Gray,
+    color: true,
+};
+
+pub const THEMES: [Theme; 2] = [CLASSIC, MONO];
+
+impl Theme {
+    pub fn by_name(name: &str) -> Option<Theme> {
+        THEMES.iter().copied().find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn next(&self) -> Theme {
+        let index = THEMES.iter().position(|t| t.name == self.name).unwrap_or(0);
+        THEMES[(index + 1) % THEMES.len()].with_color(self.color)
+    }
+
+    pub fn with_color(self, color: bool) -> Theme {
+        Theme { color, ..self }
+    }
+
+    /// Foreground style for `color`, or a plain style when color is disabled.
+    pub fn fg(&self, color: Color) -> Style {
+        self.colors(Some(color), None)
+    }
+
+    /// Background style for `color`, or a plain style when color is disabled.
+    pub fn bg(&self, color: Color) -> Style {
+        self.colors(None, Some(color))
+    }
+
+    pub fn colors(&self, fg: Option<Color>, bg: Option<Color>) -> Style {
+        let mut style = Style::default();
+        if self.color {
+            if let Some(fg) = fg {
+                style = style.fg(fg);
+            }
+            if let Some(bg) = bg {
+                style = style.bg(bg);
+            }
+        }
+        style
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        CLASSIC
+    }
+}
+
+/// Honors the NO_COLOR convention (https://no-color.org): any non-empty value disables color.
+pub fn color_enabled() -> bool {
+    env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}